@@ -0,0 +1,298 @@
+use proc_macro2::Span;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parse;
+use syn::{
+    parse_quote, Attribute, Error, Expr, Ident, LitBool, LitStr, Path, Result, Token, Visibility,
+};
+
+#[derive(Default)]
+pub struct StructAttrs {
+    pub typestate: bool,
+    // Unset fields are taken from the struct's own Default impl.
+    pub default: bool,
+    // Called with the builder before `build` assembles the struct.
+    pub validate: Option<Expr>,
+    // Defaults for the `setter(...)` options of every field.
+    pub setter: SetterAttrs,
+    pub pattern: Pattern,
+    // Generate `to_builder()` and `From<T> for TBuilder`.
+    pub derive_to_builder: bool,
+    // The builder's name and visibility, which its error type follows.
+    pub name: Option<Ident>,
+    pub vis: Option<Visibility>,
+    // Extra derives for the builder type.
+    pub derives: Vec<Path>,
+    pub build_fn: BuildFn,
+    // Generate code for `#![no_std]` crates, which need `extern crate alloc`.
+    pub no_std: bool,
+    // Derive Serialize and Deserialize for the builder and generate `merge`.
+    pub serde: bool,
+}
+
+// `build_fn(name = "...", vis = "...", skip)`.
+#[derive(Default)]
+pub struct BuildFn {
+    pub name: Option<Ident>,
+    pub vis: Option<Visibility>,
+    // Leave `build` to be written by hand.
+    pub skip: bool,
+}
+
+// How setters and `build` take the builder.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Pattern {
+    // `&mut self`; `build` clones the fields out.
+    #[default]
+    Mutable,
+    // `self`; `build` moves the fields out so they need not be Clone.
+    Owned,
+    // `&self`; setters return an updated clone of the builder.
+    Immutable,
+}
+
+#[derive(Default)]
+pub struct FieldAttrs {
+    // The field's name within the builder; required to get anything but `_0`,
+    // `_1`, ... for the fields of a tuple struct.
+    pub name: Option<Ident>,
+    pub each: Option<Each>,
+    // The field's type derives Builder too and is built through its builder.
+    pub sub_builder: bool,
+    // Not part of the builder at all; `build` always uses the default.
+    pub skip: bool,
+    // The field's setters are private to the module.
+    pub private: bool,
+    // Also generate `try_<setter>`, taking anything that converts with TryInto.
+    pub try_setter: bool,
+    pub default: Option<Expr>,
+    // Called with the new value inside the setter.
+    pub validate: Option<Expr>,
+    pub setter: SetterAttrs,
+}
+
+// `each = "..."` or `each(name = "...", into)`.
+pub struct Each {
+    pub name: Ident,
+    // Accept `impl Into<Item>` instead of the collection's item type.
+    pub into: bool,
+}
+
+#[derive(Default)]
+pub struct SetterAttrs {
+    // Accept `impl Into<T>` instead of `T`.
+    pub into: Option<bool>,
+    // Whether the setter of an `Option<T>` field takes `T`.
+    pub strip_option: Option<bool>,
+    pub name: Option<Ident>,
+}
+
+pub fn struct_attrs(attrs: &[Attribute]) -> Result<StructAttrs> {
+    let mut parsed = StructAttrs::default();
+    let mut pattern = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("typestate") {
+                parsed.typestate = true;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                parsed.default = true;
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                parsed.serde = true;
+                Ok(())
+            } else if meta.path.is_ident("no_std") {
+                parsed.no_std = true;
+                Ok(())
+            } else if meta.path.is_ident("derive_to_builder") {
+                parsed.derive_to_builder = true;
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                parsed.validate = Some(parse_str(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("setter") {
+                setter_attrs(&meta, &mut parsed.setter, false)
+            } else if meta.path.is_ident("name") {
+                parsed.name = Some(parse_str(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("vis") {
+                parsed.vis = Some(parse_str(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|meta| {
+                    parsed.derives.push(meta.path);
+                    Ok(())
+                })
+            } else if meta.path.is_ident("build_fn") {
+                build_fn_attrs(&meta, &mut parsed.build_fn)
+            } else if meta.path.is_ident("pattern") {
+                let lit: LitStr = meta.value()?.parse()?;
+                parsed.pattern = match lit.value().as_str() {
+                    "mutable" => Pattern::Mutable,
+                    "owned" => Pattern::Owned,
+                    "immutable" => Pattern::Immutable,
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            "expected \"owned\", \"mutable\" or \"immutable\"",
+                        ))
+                    }
+                };
+                pattern = Some(lit);
+                Ok(())
+            } else {
+                Err(meta.error("unrecognized builder attribute"))
+            }
+        })?;
+    }
+
+    if parsed.typestate && parsed.serde {
+        return Err(Error::new(
+            Span::call_site(),
+            "typestate builders cannot be deserialized, since their type depends on which fields are set",
+        ));
+    }
+    if parsed.typestate {
+        if let Some(lit) = pattern.filter(|_| parsed.pattern != Pattern::Owned) {
+            return Err(Error::new(
+                lit.span(),
+                "typestate builders always use the owned pattern",
+            ));
+        }
+        parsed.pattern = Pattern::Owned;
+    }
+
+    Ok(parsed)
+}
+
+pub fn field_attrs(attrs: &[Attribute]) -> Result<FieldAttrs> {
+    let mut parsed = FieldAttrs::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                parsed.name = Some(parse_str(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("each") {
+                parsed.each = Some(if meta.input.peek(Token![=]) {
+                    Each {
+                        name: parse_str(&meta)?,
+                        into: false,
+                    }
+                } else {
+                    each_attrs(&meta)?
+                });
+                Ok(())
+            } else if meta.path.is_ident("sub_builder") {
+                parsed.sub_builder = true;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                parsed.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("private") {
+                parsed.private = true;
+                Ok(())
+            } else if meta.path.is_ident("try_setter") {
+                parsed.try_setter = true;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                parsed.default = Some(if meta.input.peek(Token![=]) {
+                    parse_str(&meta)?
+                } else {
+                    parse_quote!(::core::default::Default::default())
+                });
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                parsed.validate = Some(parse_str(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("setter") {
+                setter_attrs(&meta, &mut parsed.setter, true)
+            } else {
+                Err(Error::new_spanned(
+                    &attr.meta,
+                    "expected `builder(each = \"...\")`",
+                ))
+            }
+        })?;
+    }
+
+    Ok(parsed)
+}
+
+// `setter(into, strip_option = false, name = "...")`. Renaming only makes sense
+// for a single field.
+fn setter_attrs(meta: &ParseNestedMeta, parsed: &mut SetterAttrs, field: bool) -> Result<()> {
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("into") {
+            parsed.into = Some(flag(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("strip_option") {
+            parsed.strip_option = Some(flag(&meta)?);
+            Ok(())
+        } else if field && meta.path.is_ident("name") {
+            parsed.name = Some(parse_str(&meta)?);
+            Ok(())
+        } else {
+            Err(meta.error("unrecognized setter option"))
+        }
+    })
+}
+
+fn build_fn_attrs(meta: &ParseNestedMeta, parsed: &mut BuildFn) -> Result<()> {
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            parsed.name = Some(parse_str(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("vis") {
+            parsed.vis = Some(parse_str(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            parsed.skip = flag(&meta)?;
+            Ok(())
+        } else {
+            Err(meta.error("unrecognized build_fn option"))
+        }
+    })
+}
+
+fn each_attrs(meta: &ParseNestedMeta) -> Result<Each> {
+    let mut name = None;
+    let mut into = false;
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(parse_str(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("into") {
+            into = flag(&meta)?;
+            Ok(())
+        } else {
+            Err(meta.error("unrecognized each option"))
+        }
+    })?;
+    match name {
+        Some(name) => Ok(Each { name, into }),
+        None => Err(meta.error("expected `each(name = \"...\")`")),
+    }
+}
+
+// `key = "..."`, with the string parsed as Rust syntax.
+fn parse_str<T: Parse>(meta: &ParseNestedMeta) -> Result<T> {
+    let lit: LitStr = meta.value()?.parse()?;
+    lit.parse()
+}
+
+// Either a bare `key` or `key = true/false`.
+fn flag(meta: &ParseNestedMeta) -> Result<bool> {
+    if meta.input.peek(Token![=]) {
+        let lit: LitBool = meta.value()?.parse()?;
+        Ok(lit.value)
+    } else {
+        Ok(true)
+    }
+}
@@ -0,0 +1,1245 @@
+use crate::attr::{self, Pattern, StructAttrs};
+use crate::ty::{self, Item};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Expr, Fields, GenericParam, Generics, Ident, Member,
+    Result, Type, Visibility,
+};
+
+// One builder to generate: for a struct, or for each variant of an enum.
+struct Target<'a> {
+    input: &'a DeriveInput,
+    attrs: &'a StructAttrs,
+    builder: Ident,
+    error: Ident,
+    // Associated function of the input type that returns an empty builder.
+    constructor: Ident,
+    // The struct or variant in a struct expression: `Command` or `Shape::Circle`.
+    path: TokenStream,
+    fields: Vec<Field<'a>>,
+}
+
+struct Field<'a> {
+    // Name of the field in the builder, `_0` etc. for tuple fields.
+    ident: Ident,
+    // Name of the field in the built value.
+    member: Member,
+    ty: &'a Type,
+    kind: Kind<'a>,
+    // What `build` produces when the field was never set, or None if setting
+    // it is required.
+    unset: Option<TokenStream>,
+    validate: Option<Expr>,
+    // Name of the all-at-once setter.
+    setter: Ident,
+    into: bool,
+    // Visibility of the field's setters.
+    vis: TokenStream,
+    try_setter: bool,
+}
+
+enum Kind<'a> {
+    // Any other type; must be set unless it has a default.
+    Required,
+    // Declared as `Option<T>` and the setter takes the inner `T`. Without
+    // strip_option these are Required with a fallback of None.
+    Optional(&'a Type),
+    // A collection with `#[builder(each = "...")]`, which gets a setter that
+    // extends it by one item.
+    Each {
+        setter: Ident,
+        item: Item<'a>,
+        into: bool,
+    },
+    // `#[builder(sub_builder)]`, holding the builder of the field's type which
+    // is reached through `field_mut()` and built along with this one.
+    Sub(Type),
+    // `#[builder(skip)]`, which has no place in the builder and is always
+    // filled with its default.
+    Skip,
+}
+
+impl<'a> Field<'a> {
+    fn parse(index: usize, field: &'a syn::Field, struct_attrs: &StructAttrs) -> Result<Self> {
+        let ty = &field.ty;
+        let attrs = attr::field_attrs(&field.attrs)?;
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+        let ident = match (attrs.name, &field.ident) {
+            (Some(name), _) => name,
+            (None, Some(ident)) => ident.clone(),
+            (None, None) => format_ident!("_{}", index),
+        };
+
+        if attrs.skip && (attrs.sub_builder || attrs.each.is_some() || attrs.validate.is_some()) {
+            return Err(Error::new_spanned(
+                field,
+                "`skip` cannot be combined with `sub_builder`, `each` or `validate`",
+            ));
+        }
+        if attrs.sub_builder && (attrs.each.is_some() || attrs.validate.is_some()) {
+            return Err(Error::new_spanned(
+                field,
+                "`sub_builder` cannot be combined with `each` or `validate`",
+            ));
+        }
+        if let (Some(each), Some(validate)) = (&attrs.each, &attrs.validate) {
+            let mut error =
+                Error::new_spanned(validate, "`validate` cannot be combined with `each`");
+            error.combine(Error::new(each.name.span(), "`each` setter declared here"));
+            return Err(error);
+        }
+
+        let option = ty::inner_type(ty, "Option");
+        let strip_option = attrs
+            .setter
+            .strip_option
+            .or(struct_attrs.setter.strip_option)
+            .unwrap_or(true);
+
+        if attrs.try_setter && attrs.validate.is_some() {
+            return Err(Error::new_spanned(
+                field,
+                "`try_setter` cannot be combined with `validate`",
+            ));
+        }
+
+        let kind = if attrs.skip {
+            Kind::Skip
+        } else if attrs.sub_builder {
+            let Some(builder) = ty::builder_type(ty) else {
+                return Err(Error::new_spanned(
+                    ty,
+                    "`sub_builder` requires a field whose type derives Builder",
+                ));
+            };
+            Kind::Sub(builder)
+        } else if let Some(each) = attrs.each {
+            Kind::Each {
+                setter: each.name,
+                item: ty::collection_item(ty),
+                into: each.into,
+            }
+        } else if let (Some(inner), true) = (option, strip_option) {
+            Kind::Optional(inner)
+        } else {
+            Kind::Required
+        };
+
+        let unset = if let Some(default) = attrs.default {
+            Some(quote!(#default))
+        } else if struct_attrs.default {
+            Some(quote!(__default.#member))
+        } else if option.is_some() {
+            Some(quote!(::core::option::Option::None))
+        } else {
+            match &kind {
+                // An untouched sub-builder is built from scratch instead.
+                Kind::Required | Kind::Optional(_) | Kind::Sub(_) => None,
+                Kind::Each { .. } | Kind::Skip => Some(quote!(::core::default::Default::default())),
+            }
+        };
+
+        let field = Field {
+            setter: attrs.setter.name.unwrap_or_else(|| ident.clone()),
+            ident,
+            member,
+            ty,
+            kind,
+            unset,
+            validate: attrs.validate,
+            into: attrs
+                .setter
+                .into
+                .or(struct_attrs.setter.into)
+                .unwrap_or(false),
+            vis: if attrs.private {
+                TokenStream::new()
+            } else {
+                quote!(pub)
+            },
+            try_setter: attrs.try_setter,
+        };
+        if field.try_setter && !field.has_setter() {
+            return Err(Error::new_spanned(
+                &field.setter,
+                "`try_setter` needs a field with a setter taking the whole value",
+            ));
+        }
+        Ok(field)
+    }
+
+    fn is_required(&self) -> bool {
+        self.unset.is_none() && !matches!(self.kind, Kind::Sub(_))
+    }
+
+    // Whether the builder has a place for the field.
+    fn is_stored(&self) -> bool {
+        !matches!(self.kind, Kind::Skip)
+    }
+
+    // Converts `value`, the builder's stored value, to the field's type.
+    fn wrap(&self, value: TokenStream) -> TokenStream {
+        match &self.kind {
+            Kind::Optional(_) => quote!(::core::option::Option::Some(#value)),
+            Kind::Required | Kind::Each { .. } | Kind::Sub(_) | Kind::Skip => value,
+        }
+    }
+
+    // The type held inside the builder's `Option` for this field.
+    fn storage_ty(&self) -> &Type {
+        match &self.kind {
+            Kind::Optional(inner) => inner,
+            Kind::Sub(builder) => builder,
+            Kind::Required | Kind::Each { .. } | Kind::Skip => self.ty,
+        }
+    }
+
+    // The all-at-once setter's parameter, and the statement turning it into the
+    // stored type if the setter takes `impl Into<T>`.
+    fn setter_arg(&self) -> (TokenStream, Option<TokenStream>) {
+        let ident = &self.ident;
+        let ty = self.storage_ty();
+        if self.into {
+            (
+                quote!(#ident: impl ::core::convert::Into<#ty>),
+                Some(quote!(let #ident: #ty = ::core::convert::Into::into(#ident);)),
+            )
+        } else {
+            (quote!(#ident: #ty), None)
+        }
+    }
+
+    // Runs the field's validator, if any, on the setter's argument.
+    fn check(&self) -> Option<TokenStream> {
+        let ident = &self.ident;
+        self.validate
+            .as_ref()
+            .map(|validate| quote!((#validate)(&#ident)?;))
+    }
+
+    // The return type and value of a setter, which become a Result if the
+    // field has a validator.
+    fn setter_return(
+        &self,
+        error: &Ident,
+        ty: TokenStream,
+        value: TokenStream,
+    ) -> (TokenStream, TokenStream) {
+        if self.validate.is_some() {
+            (
+                quote!(::core::result::Result<#ty, #error>),
+                quote!(::core::result::Result::Ok(#value)),
+            )
+        } else {
+            (ty, value)
+        }
+    }
+
+    // Builds a sub-builder field from the builder's Option of the sub-builder,
+    // and prefixes the path of any missing field with this one's. The
+    // sub-builder is built through `__build`, whose receiver is the same
+    // whatever its own pattern is. Unless this builder is consumed, that is
+    // done on a clone so that building again gives the same result even if
+    // the sub-builder is owned and emptied by `__build`.
+    fn build_sub(&self, target: &Target, pattern: Pattern) -> TokenStream {
+        let error = &target.error;
+        let alloc = alloc(target.attrs);
+        let ident = &self.ident;
+        let ty = self.ty;
+        let name = ident.unraw().to_string();
+        // Typestate builders have no `__build`, which is reported on the field.
+        let build = quote_spanned!(ty.span()=> __build);
+        let (place, binding, built) = match pattern {
+            Pattern::Owned => (
+                quote!(self.#ident),
+                quote!(mut builder),
+                quote!(builder.#build()),
+            ),
+            Pattern::Mutable | Pattern::Immutable => (
+                quote!(&self.#ident),
+                quote!(builder),
+                quote!(::core::clone::Clone::clone(builder).#build()),
+            ),
+        };
+        let unset = match &self.unset {
+            Some(unset) => quote!(::core::result::Result::Ok(#unset)),
+            None => quote!(<#ty>::builder().#build()),
+        };
+        quote! {
+            match #place {
+                ::core::option::Option::Some(#binding) => #built,
+                ::core::option::Option::None => #unset,
+            }
+            .map_err(|error| match error.field_path() {
+                ::core::option::Option::Some(path) => #error::MissingNestedField {
+                    path: #alloc::format!("{}.{}", #name, path),
+                },
+                ::core::option::Option::None => {
+                    #error::Validation(#alloc::string::ToString::to_string(&error))
+                }
+            })?
+        }
+    }
+
+    // `try_<setter>`, which converts its argument with TryInto and hands it to
+    // the setter, or returns the conversion's error.
+    fn try_setter(&self, receiver: TokenStream, returns: TokenStream) -> Option<TokenStream> {
+        if !self.try_setter {
+            return None;
+        }
+        let vis = &self.vis;
+        let ident = &self.ident;
+        let setter = &self.setter;
+        let name = format_ident!("try_{}", setter.unraw());
+        let ty = self.storage_ty();
+        Some(quote! {
+            #vis fn #name<__Value: ::core::convert::TryInto<#ty>>(
+                #receiver,
+                #ident: __Value,
+            ) -> ::core::result::Result<#returns, __Value::Error> {
+                let #ident: #ty = ::core::convert::TryInto::try_into(#ident)?;
+                ::core::result::Result::Ok(self.#setter(#ident))
+            }
+        })
+    }
+
+    // Whether the all-at-once setter would collide with the `each` setter.
+    fn has_setter(&self) -> bool {
+        match &self.kind {
+            Kind::Each { setter, .. } => *setter != self.setter,
+            Kind::Required | Kind::Optional(_) => true,
+            Kind::Sub(_) | Kind::Skip => false,
+        }
+    }
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = attr::struct_attrs(&input.attrs)?;
+    let ident = &input.ident;
+
+    let targets = match &input.data {
+        Data::Struct(data) => {
+            let builder = match &attrs.name {
+                Some(name) => name.clone(),
+                None => format_ident!("{}Builder", ident),
+            };
+            vec![Target {
+                input,
+                attrs: &attrs,
+                error: format_ident!("{}Error", builder),
+                builder,
+                constructor: format_ident!("builder"),
+                path: quote!(#ident),
+                fields: parse_fields(&data.fields, &attrs)?,
+            }]
+        }
+        Data::Enum(data) => {
+            if attrs.default {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`builder(default)` on an enum is not supported, put it on the fields instead",
+                ));
+            }
+            if attrs.derive_to_builder {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`builder(derive_to_builder)` is not supported on enums",
+                ));
+            }
+            if let Some(name) = &attrs.name {
+                return Err(Error::new_spanned(
+                    name,
+                    "each variant of an enum has its own builder, which cannot be renamed",
+                ));
+            }
+            data.variants
+                .iter()
+                .map(|variant| {
+                    let name = &variant.ident;
+                    Ok(Target {
+                        input,
+                        attrs: &attrs,
+                        builder: format_ident!("{}{}Builder", ident, name),
+                        error: format_ident!("{}{}BuilderError", ident, name),
+                        constructor: format_ident!("{}_builder", snake_case(name)),
+                        path: quote!(#ident::#name),
+                        fields: parse_fields(&variant.fields, &attrs)?,
+                    })
+                })
+                .collect::<Result<_>>()?
+        }
+        Data::Union(data) => {
+            let union_token = data.union_token;
+            return Err(Error::new_spanned(
+                quote!(#union_token #ident),
+                "Builder cannot be derived for unions",
+            ));
+        }
+    };
+
+    Ok(targets
+        .iter()
+        .map(|target| {
+            if attrs.typestate {
+                typestate(target)
+            } else {
+                runtime(target)
+            }
+        })
+        .collect())
+}
+
+fn parse_fields<'a>(fields: &'a Fields, attrs: &StructAttrs) -> Result<Vec<Field<'a>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| Field::parse(index, field, attrs))
+        .collect()
+}
+
+// `HttpRequest` -> `http_request`, with a run of capitals taken as one word:
+// `HTTPRequest` -> `http_request`.
+fn snake_case(ident: &Ident) -> String {
+    let chars: Vec<char> = ident.unraw().to_string().chars().collect();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let after_word = i > 0 && !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let ends_run = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_word || ends_run {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+// Binds `__default` for the fallbacks of a struct-level `#[builder(default)]`.
+fn struct_default(target: &Target) -> Option<TokenStream> {
+    let ident = &target.input.ident;
+    let (_, ty_generics, _) = target.input.generics.split_for_impl();
+    target.attrs.default.then(|| {
+        quote! {
+            let __default: #ident #ty_generics = ::core::default::Default::default();
+        }
+    })
+}
+
+// The arguments naming each of the input's own generic parameters, for
+// appending the typestate parameters after them.
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
+
+// The visibility of the builder, its error type and the function creating it.
+// Without `vis` these keep the input's, with the constructor being `pub` like
+// any other method.
+fn visibility<'a>(target: &Target<'a>) -> (&'a Visibility, TokenStream) {
+    match &target.attrs.vis {
+        Some(vis) => (vis, quote!(#vis)),
+        None => (&target.input.vis, quote!(pub)),
+    }
+}
+
+// `#[derive(...)]` for the builder type, including Clone if the builder needs
+// it and it was not requested.
+fn derives(attrs: &StructAttrs, clone: bool) -> Option<TokenStream> {
+    let mut derives: Vec<TokenStream> = attrs.derives.iter().map(|path| quote!(#path)).collect();
+    let has_clone = attrs.derives.iter().any(|path| {
+        path.segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Clone")
+    });
+    if clone && !has_clone {
+        derives.push(quote!(::core::clone::Clone));
+    }
+    if attrs.serde {
+        derives.push(quote!(::serde::Serialize));
+        derives.push(quote!(::serde::Deserialize));
+    }
+    (!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]))
+}
+
+// The crate that String and `format!` come from, which is `alloc` for
+// `#[builder(no_std)]`. Everything else the generated code uses is in `core`.
+fn alloc(attrs: &StructAttrs) -> TokenStream {
+    if attrs.no_std {
+        quote!(::alloc)
+    } else {
+        quote!(::std)
+    }
+}
+
+// The visibility and name of `build`.
+fn build_fn(attrs: &StructAttrs) -> (TokenStream, Ident) {
+    let vis = match &attrs.build_fn.vis {
+        Some(vis) => quote!(#vis),
+        None => quote!(pub),
+    };
+    let name = match &attrs.build_fn.name {
+        Some(name) => name.clone(),
+        None => format_ident!("build"),
+    };
+    (vis, name)
+}
+
+// Keeps all of the input's generic parameters in use by the builder, even the
+// ones that only appear in another variant or in a typestate field that is
+// still `()`.
+fn marker(target: &Target) -> TokenStream {
+    let ident = &target.input.ident;
+    let (_, ty_generics, _) = target.input.generics.split_for_impl();
+    quote!(__marker: ::core::marker::PhantomData<fn() -> #ident #ty_generics>)
+}
+
+// The default mode: every field is stored as an Option and `build` reports the
+// first missing one at runtime.
+fn runtime(target: &Target) -> TokenStream {
+    let Target {
+        input,
+        attrs,
+        builder,
+        error,
+        constructor,
+        path,
+        fields,
+    } = target;
+    let ident = &input.ident;
+    let (vis, constructor_vis) = visibility(target);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let stored = fields.iter().filter(|field| field.is_stored());
+    // Unset fields are left out when serialized and may be missing when
+    // deserialized.
+    let (optional, skip) = if attrs.serde {
+        (
+            Some(quote! {
+                #[serde(default, skip_serializing_if = "::core::option::Option::is_none")]
+            }),
+            Some(quote!(#[serde(skip)])),
+        )
+    } else {
+        (None, None)
+    };
+    let storage = stored.clone().map(|field| {
+        let ident = &field.ident;
+        let ty = field.storage_ty();
+        quote!(#optional #ident: ::core::option::Option<#ty>)
+    });
+    let idents: Vec<&Ident> = stored.map(|field| &field.ident).collect();
+    let marker = marker(target);
+
+    let pattern = attrs.pattern;
+    let setters = fields
+        .iter()
+        .map(|field| optional_setters(field, pattern, error));
+
+    let seed = struct_default(target);
+    let (receiver, this, fields_of) = match pattern {
+        Pattern::Mutable => (quote!(&mut self), quote!(&*self), quote!(&self)),
+        Pattern::Owned => (quote!(self), quote!(&self), quote!(self)),
+        Pattern::Immutable => (quote!(&self), quote!(self), quote!(&self)),
+    };
+    let validate = attrs
+        .validate
+        .as_ref()
+        .map(|validate| quote!((#validate)(#this)?;));
+    let build = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let member = &field.member;
+        if let (Kind::Skip, Some(unset)) = (&field.kind, &field.unset) {
+            return quote!(#member: #unset);
+        }
+        if let Kind::Sub(_) = field.kind {
+            let value = field.build_sub(target, pattern);
+            return quote!(#member: #value);
+        }
+        let value = field.wrap(match pattern {
+            Pattern::Owned => quote!(value),
+            Pattern::Mutable | Pattern::Immutable => quote!(::core::clone::Clone::clone(value)),
+        });
+        let unset = field.unset.clone().unwrap_or_else(|| {
+            let name = ident.unraw().to_string();
+            quote! {
+                return ::core::result::Result::Err(#error::MissingField { name: #name })
+            }
+        });
+        quote! {
+            #member: match #fields_of.#ident {
+                ::core::option::Option::Some(value) => #value,
+                ::core::option::Option::None => #unset,
+            }
+        }
+    });
+
+    let error_enum = error_enum(target);
+    // Immutable setters clone the builder. A mutable `build` clones every field
+    // out of it already, so the builder can be cloned as well, and has to be to
+    // serve as a sub-builder.
+    let clone = match pattern {
+        Pattern::Immutable => true,
+        Pattern::Mutable => !attrs.build_fn.skip,
+        Pattern::Owned => false,
+    };
+    let derives = derives(attrs, clone);
+    let to_builder = to_builder(target, quote!(#builder #ty_generics));
+    let merge = merge(target);
+
+    let (build_vis, build_name) = build_fn(attrs);
+    // `__build` is how an outer builder builds this one as a sub-builder, with
+    // the same visibility as `build`. An owned builder is left empty by it, as
+    // it would be by `build`.
+    let take = match pattern {
+        Pattern::Mutable | Pattern::Immutable => quote!(self),
+        Pattern::Owned => quote! {
+            ::core::mem::replace(self, Self {
+                #(#idents: ::core::option::Option::None,)*
+                __marker: ::core::marker::PhantomData,
+            })
+        },
+    };
+    let build = (!attrs.build_fn.skip).then(|| {
+        quote! {
+            #build_vis fn #build_name(#receiver) -> ::core::result::Result<#ident #ty_generics, #error> {
+                #validate
+                #seed
+                ::core::result::Result::Ok(#path {
+                    #(#build,)*
+                })
+            }
+
+            #[doc(hidden)]
+            #build_vis fn __build(&mut self) -> ::core::result::Result<#ident #ty_generics, #error> {
+                #take.#build_name()
+            }
+        }
+    });
+
+    quote! {
+        #derives
+        #vis struct #builder #generics #where_clause {
+            #(#storage,)*
+            #skip
+            #marker,
+        }
+
+        #error_enum
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #constructor_vis fn #constructor() -> #builder #ty_generics {
+                #builder {
+                    #(#idents: ::core::option::Option::None,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #to_builder
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #merge
+
+            #build
+        }
+    }
+}
+
+// `#[builder(derive_to_builder)]`: a builder with every field already set to
+// the value's, either by cloning them out of a reference or by taking them.
+fn to_builder(target: &Target, builder_ty: TokenStream) -> Option<TokenStream> {
+    if !target.attrs.derive_to_builder {
+        return None;
+    }
+
+    let ident = &target.input.ident;
+    let builder = &target.builder;
+    let (impl_generics, ty_generics, where_clause) = target.input.generics.split_for_impl();
+
+    let populate = |by_ref: bool| {
+        target
+            .fields
+            .iter()
+            .filter(|field| field.is_stored())
+            .map(move |field| {
+                let ident = &field.ident;
+                let member = &field.member;
+                let value = if by_ref {
+                    quote!(::core::clone::Clone::clone(&self.#member))
+                } else {
+                    quote!(value.#member)
+                };
+                let stored = match &field.kind {
+                    Kind::Sub(_) if by_ref => {
+                        quote!(::core::option::Option::Some(self.#member.to_builder()))
+                    }
+                    Kind::Sub(_) => {
+                        quote!(::core::option::Option::Some(::core::convert::From::from(#value)))
+                    }
+                    Kind::Optional(_) => value,
+                    // Required fields of a typestate builder are stored as is.
+                    Kind::Required if target.attrs.typestate && field.is_required() => value,
+                    Kind::Required | Kind::Each { .. } | Kind::Skip => {
+                        quote!(::core::option::Option::Some(#value))
+                    }
+                };
+                quote!(#ident: #stored)
+            })
+    };
+    let by_ref = populate(true);
+    let by_value = populate(false);
+
+    Some(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn to_builder(&self) -> #builder_ty {
+                #builder {
+                    #(#by_ref,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #builder_ty #where_clause {
+            fn from(value: #ident #ty_generics) -> Self {
+                #builder {
+                    #(#by_value,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+    })
+}
+
+// `#[builder(serde)]`: overlays the fields set in another builder, such as one
+// deserialized from a more specific layer of configuration. Sub-builders are
+// merged field by field through `__merge`, whose receiver is the same for every
+// pattern. Like `__build`, it is only as visible as `build`, since a builder
+// cannot be nested anywhere it cannot be built.
+fn merge(target: &Target) -> Option<TokenStream> {
+    if !target.attrs.serde {
+        return None;
+    }
+    let (vis, _) = build_fn(target.attrs);
+
+    let overlay = target
+        .fields
+        .iter()
+        .filter(|field| field.is_stored())
+        .map(|field| {
+            let ident = &field.ident;
+            match field.kind {
+                Kind::Sub(_) => quote! {
+                    match (&mut self.#ident, other.#ident) {
+                        (::core::option::Option::Some(builder), ::core::option::Option::Some(other)) => {
+                            builder.__merge(other)
+                        }
+                        (builder, other @ ::core::option::Option::Some(_)) => *builder = other,
+                        (_, ::core::option::Option::None) => {}
+                    }
+                },
+                Kind::Required | Kind::Optional(_) | Kind::Each { .. } | Kind::Skip => quote! {
+                    if other.#ident.is_some() {
+                        self.#ident = other.#ident;
+                    }
+                },
+            }
+        });
+    let Chain {
+        receiver,
+        returns,
+        copy,
+        this,
+    } = Chain::new(target.attrs.pattern);
+
+    Some(quote! {
+        #[doc(hidden)]
+        #vis fn __merge(&mut self, other: Self) {
+            #(#overlay)*
+        }
+
+        pub fn merge(#receiver, other: Self) -> #returns {
+            #copy
+            #this.__merge(other);
+            #this
+        }
+    })
+}
+
+// How a method that updates the builder takes and returns it.
+struct Chain {
+    receiver: TokenStream,
+    returns: TokenStream,
+    // The immutable pattern modifies and returns a copy of the builder.
+    copy: Option<TokenStream>,
+    this: TokenStream,
+}
+
+impl Chain {
+    fn new(pattern: Pattern) -> Self {
+        let (receiver, returns) = match pattern {
+            Pattern::Mutable => (quote!(&mut self), quote!(&mut Self)),
+            Pattern::Owned => (quote!(mut self), quote!(Self)),
+            Pattern::Immutable => (quote!(&self), quote!(Self)),
+        };
+        let (copy, this) = match pattern {
+            Pattern::Mutable | Pattern::Owned => (None, quote!(self)),
+            Pattern::Immutable => (
+                Some(quote!(let mut __builder = ::core::clone::Clone::clone(self);)),
+                quote!(__builder),
+            ),
+        };
+        Chain {
+            receiver,
+            returns,
+            copy,
+            this,
+        }
+    }
+}
+
+// Setters of a field stored as an Option in the builder, which is every field
+// except the required ones of a typestate builder.
+fn optional_setters(field: &Field, pattern: Pattern, error: &Ident) -> TokenStream {
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let Chain {
+        receiver,
+        returns,
+        copy,
+        this,
+    } = Chain::new(pattern);
+
+    let setter = field.has_setter().then(|| {
+        let name = &field.setter;
+        let (arg, convert) = field.setter_arg();
+        let check = field.check();
+        let (ret, value) = field.setter_return(error, returns.clone(), this.clone());
+        quote! {
+            #vis fn #name(#receiver, #arg) -> #ret {
+                #convert
+                #check
+                #copy
+                #this.#ident = ::core::option::Option::Some(#ident);
+                #value
+            }
+        }
+    });
+    let try_setter = field.try_setter(
+        match pattern {
+            Pattern::Mutable => quote!(&mut self),
+            Pattern::Owned => quote!(self),
+            Pattern::Immutable => quote!(&self),
+        },
+        returns.clone(),
+    );
+
+    let each = match &field.kind {
+        Kind::Each { setter, item, into } => {
+            let ty = field.ty;
+            let arg = |ident: &Ident, ty: &Type| {
+                if *into {
+                    (
+                        quote!(#ident: impl ::core::convert::Into<#ty>),
+                        Some(quote!(let #ident: #ty = ::core::convert::Into::into(#ident);)),
+                    )
+                } else {
+                    (quote!(#ident: #ty), None)
+                }
+            };
+            let (generics, args, converts, item, bound) = match item {
+                Item::Element(element) => {
+                    let (arg, convert) = arg(setter, element);
+                    (None, vec![arg], vec![convert], quote!(#setter), None)
+                }
+                Item::Entry(key_ty, value_ty) => {
+                    let key = Ident::new("key", Span::call_site());
+                    let value = Ident::new("value", Span::call_site());
+                    let (key_arg, key_convert) = arg(&key, key_ty);
+                    let (value_arg, value_convert) = arg(&value, value_ty);
+                    (
+                        None,
+                        vec![key_arg, value_arg],
+                        vec![key_convert, value_convert],
+                        quote!((#key, #value)),
+                        None,
+                    )
+                }
+                // With `into` the item type can only be inferred from the
+                // collection's Extend impl, so there should be just one.
+                Item::Unknown if *into => (
+                    Some(quote!(<__Item, __Value: ::core::convert::Into<__Item>>)),
+                    vec![quote!(#setter: __Value)],
+                    vec![Some(
+                        quote!(let #setter: __Item = ::core::convert::Into::into(#setter);),
+                    )],
+                    quote!(#setter),
+                    Some(quote!(where #ty: ::core::iter::Extend<__Item>)),
+                ),
+                Item::Unknown => (
+                    Some(quote!(<__Item>)),
+                    vec![quote!(#setter: __Item)],
+                    Vec::new(),
+                    quote!(#setter),
+                    Some(quote!(where #ty: ::core::iter::Extend<__Item>)),
+                ),
+            };
+            Some(quote! {
+                #vis fn #setter #generics(#receiver, #(#args),*) -> #returns #bound {
+                    #(#converts)*
+                    #copy
+                    ::core::iter::Extend::extend(
+                        #this.#ident.get_or_insert_with(::core::default::Default::default),
+                        ::core::iter::once(#item),
+                    );
+                    #this
+                }
+            })
+        }
+        Kind::Sub(builder) => {
+            let ty = field.ty;
+            let accessor = format_ident!("{}_mut", ident.unraw());
+            Some(quote! {
+                #vis fn #accessor(&mut self) -> &mut #builder {
+                    self.#ident.get_or_insert_with(<#ty>::builder)
+                }
+            })
+        }
+        Kind::Required | Kind::Optional(_) | Kind::Skip => None,
+    };
+
+    quote!(#setter #try_setter #each)
+}
+
+// `MissingNestedField` only exists on builders that have sub-builders, so that
+// matching on the error of any other builder stays exhaustive with two arms.
+fn error_enum(target: &Target) -> TokenStream {
+    let (vis, _) = visibility(target);
+    let error = &target.error;
+    let fields = &target.fields;
+    let alloc = alloc(target.attrs);
+    let nested = fields
+        .iter()
+        .any(|field| matches!(field.kind, Kind::Sub(_)));
+    let (variant, display, path) = if nested {
+        (
+            Some(quote!(MissingNestedField {
+                path: #alloc::string::String
+            },)),
+            Some(quote! {
+                #error::MissingNestedField { path } => {
+                    ::core::write!(formatter, "field `{}` is not set", path)
+                }
+            }),
+            Some(quote! {
+                #error::MissingNestedField { path } => {
+                    ::core::option::Option::Some(::core::clone::Clone::clone(path))
+                }
+            }),
+        )
+    } else {
+        (None, None, None)
+    };
+
+    quote! {
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis enum #error {
+            MissingField { name: &'static str },
+            #variant
+            Validation(#alloc::string::String),
+        }
+
+        impl #error {
+            /// The dotted path of the field that was not set, if this error is
+            /// about one.
+            pub fn field_path(&self) -> ::core::option::Option<#alloc::string::String> {
+                match self {
+                    #error::MissingField { name } => {
+                        ::core::option::Option::Some(#alloc::string::ToString::to_string(name))
+                    }
+                    #path
+                    #error::Validation(_) => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                match self {
+                    #error::MissingField { name } => {
+                        ::core::write!(formatter, "field `{}` is not set", name)
+                    }
+                    #display
+                    #error::Validation(message) => formatter.write_str(message),
+                }
+            }
+        }
+
+        impl ::core::error::Error for #error {}
+
+        impl ::core::convert::From<#alloc::string::String> for #error {
+            fn from(message: #alloc::string::String) -> Self {
+                #error::Validation(message)
+            }
+        }
+    }
+}
+
+// `#[builder(typestate)]`: every required field gets a type parameter on the
+// builder which is `()` until the field is set and the field's type after, and
+// `build` is only implemented once all of them hold the field's type. Setters
+// consume the builder since they change its type. There is no error type unless
+// a validator or sub-builder is involved, since nothing else can fail.
+fn typestate(target: &Target) -> TokenStream {
+    let Target {
+        input,
+        attrs,
+        builder,
+        error,
+        constructor,
+        path,
+        fields,
+    } = target;
+    let ident = &input.ident;
+    let (vis, constructor_vis) = visibility(target);
+    let fallible = attrs.validate.is_some()
+        || fields
+            .iter()
+            .any(|field| field.validate.is_some() || matches!(field.kind, Kind::Sub(_)));
+
+    let required: Vec<&Field> = fields.iter().filter(|field| field.is_required()).collect();
+    let params = state_params(&required, &input.generics);
+    let state_param = |ident: &Ident| {
+        let index = required
+            .iter()
+            .position(|field| field.ident == *ident)
+            .unwrap();
+        &params[index]
+    };
+    let required_tys = required.iter().map(|field| field.ty);
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let args = generic_args(&input.generics);
+    let mut generics = input.generics.clone();
+    generics.params.extend(
+        params
+            .iter()
+            .map(|param| -> GenericParam { parse_quote!(#param = ()) }),
+    );
+    let (state_impl_generics, state_ty_generics, _) = generics.split_for_impl();
+
+    let stored = fields.iter().filter(|field| field.is_stored());
+    let storage = stored.clone().map(|field| {
+        let ident = &field.ident;
+        if field.is_required() {
+            let param = state_param(ident);
+            quote!(#ident: #param)
+        } else {
+            let ty = field.storage_ty();
+            quote!(#ident: ::core::option::Option<#ty>)
+        }
+    });
+    let marker = marker(target);
+
+    let init = stored.clone().map(|field| {
+        let ident = &field.ident;
+        if field.is_required() {
+            quote!(#ident: ())
+        } else {
+            quote!(#ident: ::core::option::Option::None)
+        }
+    });
+
+    let setters = fields.iter().map(|field| {
+        if !field.is_required() {
+            return optional_setters(field, Pattern::Owned, error);
+        }
+        let ident = &field.ident;
+        let vis = &field.vis;
+        let name = &field.setter;
+        let (arg, convert) = field.setter_arg();
+        let check = field.check();
+        let target = required.iter().map(|other| {
+            if other.ident == *ident {
+                let ty = other.ty;
+                quote!(#ty)
+            } else {
+                let param = state_param(&other.ident);
+                quote!(#param)
+            }
+        });
+        let moved = stored.clone().map(|other| {
+            let other = &other.ident;
+            if other == ident {
+                quote!(#other)
+            } else {
+                quote!(#other: self.#other)
+            }
+        });
+        let (ret, value) = field.setter_return(
+            error,
+            quote!(#builder<#(#args,)* #(#target),*>),
+            quote! {
+                #builder {
+                    #(#moved,)*
+                    __marker: self.__marker,
+                }
+            },
+        );
+        let try_setter = field.try_setter(quote!(self), ret.clone());
+        quote! {
+            #vis fn #name(self, #arg) -> #ret {
+                #convert
+                #check
+                #value
+            }
+
+            #try_setter
+        }
+    });
+
+    let seed = struct_default(target);
+    let validate = attrs
+        .validate
+        .as_ref()
+        .map(|validate| quote!((#validate)(&self)?;));
+    let build = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let member = &field.member;
+        if let (Kind::Skip, Some(unset)) = (&field.kind, &field.unset) {
+            return quote!(#member: #unset);
+        }
+        if let Kind::Sub(_) = field.kind {
+            let value = field.build_sub(target, Pattern::Owned);
+            return quote!(#member: #value);
+        }
+        match &field.unset {
+            None => quote!(#member: self.#ident),
+            Some(unset) => {
+                let value = field.wrap(quote!(value));
+                quote! {
+                    #member: match self.#ident {
+                        ::core::option::Option::Some(value) => #value,
+                        ::core::option::Option::None => #unset,
+                    }
+                }
+            }
+        }
+    });
+
+    let built = quote!(#builder<#(#args,)* #(#required_tys),*>);
+    let to_builder = to_builder(target, built.clone());
+
+    let (error_enum, ret, value) = if fallible {
+        (
+            Some(error_enum(target)),
+            quote!(::core::result::Result<#ident #ty_generics, #error>),
+            quote!(::core::result::Result::Ok),
+        )
+    } else {
+        (None, quote!(#ident #ty_generics), TokenStream::new())
+    };
+    let derives = derives(attrs, false);
+
+    let (build_vis, build_name) = build_fn(attrs);
+    let build = (!attrs.build_fn.skip).then(|| {
+        quote! {
+            impl #impl_generics #built #where_clause {
+                #build_vis fn #build_name(self) -> #ret {
+                    #validate
+                    #seed
+                    #value(#path {
+                        #(#build,)*
+                    })
+                }
+            }
+        }
+    });
+
+    quote! {
+        #derives
+        #vis struct #builder #generics #where_clause {
+            #(#storage,)*
+            #marker,
+        }
+
+        #error_enum
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #constructor_vis fn #constructor() -> #builder #ty_generics {
+                #builder {
+                    #(#init,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #state_impl_generics #builder #state_ty_generics #where_clause {
+            #(#setters)*
+        }
+
+        #to_builder
+
+        #build
+    }
+}
+
+// The type parameters tracking whether each required field is set, like
+// `__CurrentDir` for `current_dir`. Fields such as `a_b` and `aB` that come out
+// the same, or that clash with the input's own parameters, are numbered apart.
+fn state_params(required: &[&Field], generics: &Generics) -> Vec<Ident> {
+    let mut taken: Vec<String> = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+    required
+        .iter()
+        .map(|field| {
+            let mut base = String::from("__");
+            for word in field.ident.unraw().to_string().split('_') {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    base.extend(first.to_uppercase());
+                    base.push_str(chars.as_str());
+                }
+            }
+            let mut name = base.clone();
+            let mut n = 2;
+            while taken.contains(&name) {
+                name = format!("{}{}", base, n);
+                n += 1;
+            }
+            taken.push(name.clone());
+            Ident::new(&name, field.ident.span())
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+use quote::format_ident;
+use syn::{GenericArgument, Ident, PathArguments, Type};
+
+// Macros only see tokens, so a wrapper is recognized by how the type is spelled:
+// `Option<T>`, `std::option::Option<T>` and so on all match "Option", while a
+// type alias for Option does not.
+pub fn inner_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    match type_args(ty)? {
+        (ident, args) if ident == wrapper && args.len() == 1 => Some(args[0]),
+        _ => None,
+    }
+}
+
+// The builder generated for a field's type by this derive, assuming the usual
+// name: `config::Server<T>` -> `config::ServerBuilder<T>`.
+pub fn builder_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let mut path = path.clone();
+    let segment = path.path.segments.last_mut()?;
+    segment.ident = format_ident!("{}Builder", segment.ident);
+    Some(Type::Path(path))
+}
+
+// What an `each` setter adds to a collection.
+pub enum Item<'a> {
+    // Vec, VecDeque, LinkedList, BinaryHeap, HashSet and BTreeSet.
+    Element(&'a Type),
+    // HashMap and BTreeMap, whose setter takes the key and value separately.
+    Entry(&'a Type, &'a Type),
+    // Any other type implementing Extend, with a setter generic over the item.
+    Unknown,
+}
+
+pub fn collection_item(ty: &Type) -> Item<'_> {
+    let Some((ident, args)) = type_args(ty) else {
+        return Item::Unknown;
+    };
+    let name = ident.to_string();
+    match (name.as_str(), args.as_slice()) {
+        (
+            "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "HashSet" | "BTreeSet",
+            [element, ..],
+        ) => Item::Element(element),
+        ("HashMap" | "BTreeMap", [key, value, ..]) => Item::Entry(key, value),
+        _ => Item::Unknown,
+    }
+}
+
+// The name and type arguments of the last path segment, ignoring lifetimes and
+// const arguments.
+fn type_args(ty: &Type) -> Option<(&Ident, Vec<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(generics) = &segment.arguments else {
+        return None;
+    };
+    let args = generics
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect();
+    Some((&segment.ident, args))
+}
@@ -0,0 +1,57 @@
+// With #[builder(typestate)] the builder tracks which required fields have been
+// set in its type, so `build` only exists once every field that is neither an
+// Option nor an `each` field has been given a value. Since there is nothing
+// left to fail, `build` returns the struct directly instead of a Result.
+//
+// Setters consume the builder and return it with the updated type, so a
+// typestate builder is always used by chaining.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+// Fields whose names only differ in case and underscores are tracked apart.
+#[derive(Builder)]
+#[builder(typestate)]
+#[allow(non_snake_case)]
+pub struct Point<__AB> {
+    a_b: u8,
+    aB: u16,
+    extra: __AB,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .env(vec![])
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.current_dir.is_none());
+
+    // Setting a field again replaces the previous value.
+    let command = Command::builder()
+        .executable("rustc".to_owned())
+        .executable("cargo".to_owned())
+        .env(vec!["RUST_LOG=debug".to_owned()])
+        .current_dir("..".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let point = Point::builder().aB(2).a_b(1).extra('c').build();
+    assert_eq!((point.a_b, point.aB, point.extra), (1, 2, 'c'));
+}
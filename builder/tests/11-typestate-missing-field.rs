@@ -0,0 +1,16 @@
+// Forgetting a required field on a typestate builder is a compile error rather
+// than a runtime one: `build` is not implemented for a builder whose state
+// parameter for `env` is still `()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    env: Vec<String>,
+}
+
+fn main() {
+    let _command = Command::builder().executable("cargo".to_owned()).build();
+}
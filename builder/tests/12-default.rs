@@ -0,0 +1,75 @@
+// Fields marked #[builder(default)] no longer need to be set before `build`;
+// if they were never set they get Default::default(), or the value of the
+// expression in #[builder(default = "...")].
+//
+// The struct-level #[builder(default)] takes every field that was not set from
+// the struct's own Default impl instead.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(default = "vec![\"PATH=/bin\".to_owned()]")]
+    env: Vec<String>,
+    #[builder(default = "Some(\".\".to_owned())")]
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(default)]
+pub struct Limits {
+    retries: u32,
+    timeout: Option<u64>,
+    #[builder(default = "7")]
+    backoff: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            retries: 3,
+            timeout: Some(30),
+            backoff: 1,
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    #[builder(default = "1")]
+    priority: u8,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.env, vec!["PATH=/bin"]);
+    assert_eq!(command.current_dir.as_deref(), Some("."));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.env.is_empty());
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    assert!(Command::builder().build().is_err());
+
+    let limits = Limits::builder().retries(5).build().unwrap();
+    assert_eq!(limits.retries, 5);
+    assert_eq!(limits.timeout, Some(30));
+    assert_eq!(limits.backoff, 7);
+
+    let job = Job::builder().name("fetch".to_owned()).build();
+    assert_eq!(job.priority, 1);
+}
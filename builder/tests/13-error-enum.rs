@@ -0,0 +1,38 @@
+// The `build` method of a runtime-checked builder returns a CommandBuilderError
+// generated next to the builder, so callers can match on which field was
+// missing instead of inspecting a boxed error's message. The enum implements
+// std::error::Error so `?` into Box<dyn Error> keeps working.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn build() -> Result<Command, Box<dyn Error>> {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec![])
+        .build()?;
+    Ok(command)
+}
+
+fn main() {
+    let error = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+
+    match &error {
+        CommandBuilderError::MissingField { name } => assert_eq!(*name, "args"),
+        CommandBuilderError::Validation(_) => unreachable!(),
+    }
+    assert_eq!(error.to_string(), "field `args` is not set");
+
+    assert!(build().is_ok());
+}
@@ -0,0 +1,80 @@
+// A struct-level #[builder(validate = "...")] names a function that `build`
+// calls with a reference to the builder before assembling the struct. Returning
+// Err(String) from it makes `build` fail with CommandBuilderError::Validation.
+//
+// A field-level #[builder(validate = "...")] is called with a reference to the
+// new value inside that field's setter instead, so invalid values are rejected
+// where they are passed in. Such a setter returns a Result.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "Self::check")]
+pub struct Listener {
+    host: String,
+    #[builder(validate = "check_port")]
+    port: u16,
+    tls: Option<bool>,
+    plaintext: Option<bool>,
+}
+
+fn check_port(port: &u16) -> Result<(), String> {
+    if *port < 1024 {
+        return Err(format!("port {} is privileged", port));
+    }
+    Ok(())
+}
+
+impl ListenerBuilder {
+    fn check(&self) -> Result<(), String> {
+        if self.tls == Some(true) && self.plaintext == Some(true) {
+            return Err("tls and plaintext are mutually exclusive".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate, validate = "Self::check")]
+pub struct Job {
+    name: String,
+}
+
+impl JobBuilder<String> {
+    fn check(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("name is empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), ListenerBuilderError> {
+    let listener = Listener::builder()
+        .host("localhost".to_owned())
+        .port(8080)?
+        .tls(true)
+        .build()?;
+    assert_eq!(listener.port, 8080);
+
+    let error = Listener::builder().port(80).err().unwrap();
+    assert_eq!(error.to_string(), "port 80 is privileged");
+
+    let error = Listener::builder()
+        .host("localhost".to_owned())
+        .port(8080)?
+        .tls(true)
+        .plaintext(true)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        ListenerBuilderError::Validation("tls and plaintext are mutually exclusive".to_owned()),
+    );
+
+    assert!(Job::builder().name("fetch".to_owned()).build().is_ok());
+    assert!(Job::builder().name(String::new()).build().is_err());
+
+    Ok(())
+}
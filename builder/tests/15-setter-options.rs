@@ -0,0 +1,54 @@
+// The generated setters can be adjusted with #[builder(setter(...))]:
+//
+//   - `into` makes the setter accept `impl Into<T>`;
+//   - `strip_option = false` makes the setter of an Option<T> field take the
+//     Option itself instead of T;
+//   - `name = "..."` renames the setter.
+//
+// `into` and `strip_option` can also be given on the struct, in which case
+// they apply to every field that does not say otherwise.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(name = "cwd"))]
+    current_dir: Option<PathBuf>,
+    #[builder(setter(strip_option = false))]
+    umask: Option<u32>,
+    #[builder(setter(into = false))]
+    nice: i8,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter(into, strip_option = false))]
+pub struct Job {
+    name: String,
+    parent: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build".to_owned())
+        .cwd("..")
+        .umask(None)
+        .nice(-5)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, Some(PathBuf::from("..")));
+    assert_eq!(command.umask, None);
+    assert_eq!(command.nice, -5);
+
+    let job = Job::builder().name("fetch").parent(Some("root".to_owned())).build();
+    assert_eq!(job.name, "fetch");
+    assert_eq!(job.parent.as_deref(), Some("root"));
+}
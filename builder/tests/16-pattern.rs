@@ -0,0 +1,55 @@
+// #[builder(pattern = "...")] selects how setters and `build` take the builder:
+//
+//   - "mutable" (the default) takes and returns `&mut Self`; `build` clones the
+//     fields out, so the builder can be reused;
+//   - "owned" consumes the builder and returns it by value; `build` moves the
+//     fields out, so fields do not need to implement Clone;
+//   - "immutable" takes `&self` and returns an updated copy of the builder.
+
+use derive_builder::Builder;
+
+// Does not implement Clone.
+pub struct Handle(u32);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Process {
+    handle: Handle,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "mutable")]
+pub struct Job {
+    name: String,
+}
+
+fn main() {
+    let process = Process::builder()
+        .handle(Handle(7))
+        .arg("-v".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(process.handle.0, 7);
+    assert_eq!(process.args, vec!["-v"]);
+
+    let base = Command::builder().executable("cargo".to_owned());
+    let here = base.current_dir(".".to_owned());
+    let there = base.current_dir("..".to_owned());
+    assert!(base.build().unwrap().current_dir.is_none());
+    assert_eq!(here.build().unwrap().current_dir.as_deref(), Some("."));
+    assert_eq!(there.build().unwrap().current_dir.as_deref(), Some(".."));
+
+    let mut builder = Job::builder();
+    builder.name("fetch".to_owned());
+    assert_eq!(builder.build().unwrap().name, "fetch");
+    assert_eq!(builder.build().unwrap().name, "fetch");
+}
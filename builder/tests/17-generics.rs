@@ -0,0 +1,85 @@
+// The builder carries the same lifetimes, type parameters, const parameters
+// and where-clause as the struct it builds, so the impls need the same
+// generics too.
+//
+//     pub struct RequestBuilder<'a, T: Display, const N: usize>
+//     where
+//         T: Clone,
+//     {
+//         path: Option<&'a str>,
+//         ...
+//     }
+//
+//     impl<'a, T: Display, const N: usize> RequestBuilder<'a, T, N>
+//     where
+//         T: Clone,
+//     {...}
+//
+// A typestate builder adds its own state parameters after the struct's.
+
+use derive_builder::Builder;
+use std::fmt::Display;
+
+#[derive(Builder)]
+pub struct Request<'a, T: Display, const N: usize>
+where
+    T: Clone,
+{
+    path: &'a str,
+    body: T,
+    #[builder(each = "header")]
+    headers: Vec<[u8; N]>,
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, default)]
+pub struct Response<'a, T>
+where
+    T: Default,
+{
+    #[builder(setter(into))]
+    status: u16,
+    body: T,
+    reason: &'a str,
+}
+
+impl<'a, T: Default> Default for Response<'a, T> {
+    fn default() -> Self {
+        Response {
+            status: 200,
+            body: T::default(),
+            reason: "OK",
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<K, V: Clone> {
+    key: K,
+    value: V,
+    note: Option<String>,
+}
+
+fn main() {
+    let path = String::from("/index.html");
+    let request = Request::builder()
+        .path(&path)
+        .body(42)
+        .header(*b"ab")
+        .build()
+        .unwrap();
+    assert_eq!(request.path, "/index.html");
+    assert_eq!(request.body, 42);
+    assert_eq!(request.headers, vec![*b"ab"]);
+
+    let response = Response::<Vec<u8>>::builder().status(404u16).build();
+    assert_eq!(response.status, 404);
+    assert!(response.body.is_empty());
+    assert_eq!(response.reason, "OK");
+
+    let pair = Pair::builder().value(1).key("one").build();
+    assert_eq!(pair.key, "one");
+    assert_eq!(pair.value, 1);
+}
@@ -0,0 +1,89 @@
+// Tuple structs get one setter per position, named `_0`, `_1`, ... unless the
+// field is given a name with #[builder(name = "...")].
+//
+// Enums get one builder per variant, created by an associated function named
+// after the variant:
+//
+//     impl Shape {
+//         pub fn circle_builder() -> ShapeCircleBuilder {...}
+//         pub fn rounded_rect_builder() -> ShapeRoundedRectBuilder {...}
+//         pub fn svg_path_builder() -> ShapeSVGPathBuilder {...}
+//     }
+//
+// and whose `build` returns the enum. A run of capitals such as `SVG` counts as
+// one word.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Endpoint(#[builder(name = "host")] String, u16, Option<String>);
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+    },
+    RoundedRect {
+        width: f64,
+        height: f64,
+        #[builder(default = "0.0")]
+        corner: f64,
+    },
+    Polygon(#[builder(each = "point")] Vec<(i32, i32)>),
+    SVGPath(String),
+    Empty,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<T>(T, T);
+
+fn main() {
+    let endpoint = Endpoint::builder()
+        .host("localhost".to_owned())
+        ._1(8080)
+        .build()
+        .unwrap();
+    assert_eq!(endpoint.0, "localhost");
+    assert_eq!(endpoint.1, 8080);
+    assert!(endpoint.2.is_none());
+
+    let error = Endpoint::builder()._1(80).build().err().unwrap();
+    assert_eq!(error, EndpointBuilderError::MissingField { name: "host" });
+
+    let circle = Shape::circle_builder().radius(1.5).build().unwrap();
+    assert_eq!(circle, Shape::Circle { radius: 1.5 });
+
+    let rect = Shape::rounded_rect_builder()
+        .width(2.0)
+        .height(3.0)
+        .build()
+        .unwrap();
+    assert_eq!(
+        rect,
+        Shape::RoundedRect {
+            width: 2.0,
+            height: 3.0,
+            corner: 0.0,
+        },
+    );
+
+    let polygon = Shape::polygon_builder()
+        .point((0, 0))
+        .point((1, 0))
+        .point((0, 1))
+        .build()
+        .unwrap();
+    assert_eq!(polygon, Shape::Polygon(vec![(0, 0), (1, 0), (0, 1)]));
+
+    let path = Shape::svg_path_builder()
+        ._0("M 0 0".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(path, Shape::SVGPath("M 0 0".to_owned()));
+
+    assert_eq!(Shape::empty_builder().build().unwrap(), Shape::Empty);
+
+    let pair = Pair::builder()._1("b")._0("a").build();
+    assert_eq!((pair.0, pair.1), ("a", "b"));
+}
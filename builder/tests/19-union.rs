@@ -0,0 +1,13 @@
+// Unions have no way to hold more than one field at a time, so there is
+// nothing sensible to build and the derive reports an error pointing at the
+// union.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,70 @@
+// #[builder(each = "...")] works on any collection that can be extended one
+// item at a time:
+//
+//   - for Vec, VecDeque, LinkedList, BinaryHeap, HashSet and BTreeSet the
+//     setter takes a single element;
+//   - for HashMap and BTreeMap the setter takes the key and the value as two
+//     arguments;
+//   - for any other type implementing Default and Extend, the setter is
+//     generic over the items the collection can be extended with.
+//
+// The longer form #[builder(each(name = "...", into))] makes the setter accept
+// anything convertible into the item type, or the key and value types. For
+// other collections, the item type is then inferred from their Extend impl,
+// which only works if there is a single one.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Clone, Default)]
+pub struct Tags(Vec<String>);
+
+impl Extend<String> for Tags {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+#[derive(Builder)]
+pub struct Request {
+    #[builder(each(name = "header", into))]
+    headers: HashMap<String, String>,
+    #[builder(each = "param")]
+    params: BTreeMap<&'static str, u32>,
+    #[builder(each = "flag")]
+    flags: HashSet<char>,
+    #[builder(each(name = "segment", into))]
+    path: Vec<String>,
+    #[builder(each = "tag")]
+    tags: Tags,
+    #[builder(each(name = "label", into))]
+    labels: Tags,
+}
+
+fn main() {
+    let request = Request::builder()
+        .header("Host", "example.com")
+        .header("Accept", String::from("*/*"))
+        .param("page", 2)
+        .flag('v')
+        .flag('v')
+        .segment("api")
+        .segment("v1")
+        .tag("fast".to_owned())
+        .label("fast")
+        .label(String::from("safe"))
+        .build()
+        .unwrap();
+
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.headers["Host"], "example.com");
+    assert_eq!(request.params["page"], 2);
+    assert_eq!(request.flags.len(), 1);
+    assert_eq!(request.path, vec!["api", "v1"]);
+    assert_eq!(request.tags.0, vec!["fast"]);
+    assert_eq!(request.labels.0, vec!["fast", "safe"]);
+
+    let empty = Request::builder().build().unwrap();
+    assert!(empty.headers.is_empty());
+    assert!(empty.tags.0.is_empty());
+}
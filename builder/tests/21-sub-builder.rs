@@ -0,0 +1,67 @@
+// A field whose type also derives Builder can be marked #[builder(sub_builder)].
+// Instead of a setter taking the finished value, the outer builder then holds
+// the field's own builder, reachable through a `<field>_mut()` accessor:
+//
+//     impl ConfigBuilder {
+//         pub fn server_mut(&mut self) -> &mut ServerBuilder {...}
+//     }
+//
+// `build` builds each sub-builder as well. When a nested field is missing, the
+// error names it by its dotted path from the outermost builder.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Tls {
+    cert: String,
+    key: Option<String>,
+}
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    host: String,
+    #[builder(sub_builder)]
+    tls: Tls,
+}
+
+#[derive(Builder, Debug)]
+pub struct Config {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Deployment {
+    #[builder(sub_builder)]
+    config: Config,
+}
+
+fn main() {
+    let mut builder = Config::builder();
+    builder.name("prod".to_owned());
+    builder.server_mut().host("example.com".to_owned());
+    builder.server_mut().tls_mut().cert("cert.pem".to_owned());
+    let config = builder.build().unwrap();
+    assert_eq!(config.server.host, "example.com");
+    assert_eq!(config.server.tls.cert, "cert.pem");
+    assert!(config.server.tls.key.is_none());
+
+    let mut builder = Config::builder();
+    builder.name("prod".to_owned());
+    builder.server_mut().host("example.com".to_owned());
+    let error = builder.build().unwrap_err();
+    assert_eq!(
+        error,
+        ConfigBuilderError::MissingNestedField {
+            path: "server.tls.cert".to_owned(),
+        },
+    );
+    assert_eq!(error.to_string(), "field `server.tls.cert` is not set");
+
+    let mut builder = Deployment::builder();
+    builder.config_mut().name("staging".to_owned());
+    let error = builder.build().unwrap_err();
+    assert_eq!(error.field_path().as_deref(), Some("config.server.host"));
+}
@@ -0,0 +1,56 @@
+// With #[builder(derive_to_builder)] a built value can go back to its builder,
+// with every field already set, to make a modified copy:
+//
+//     impl Command {
+//         pub fn to_builder(&self) -> CommandBuilder {...}
+//     }
+//
+//     impl From<Command> for CommandBuilder {...}
+//
+// `to_builder` clones the fields; the From impl moves them out of the value.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(derive_to_builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(derive_to_builder)]
+pub struct Limits {
+    memory: u64,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate, derive_to_builder)]
+pub struct Point(i32, i32);
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    builder.limits_mut().memory(64);
+    let command = builder.build().unwrap();
+
+    let mut builder = command.to_builder();
+    builder.arg("--release".to_owned()).current_dir("..".to_owned());
+    builder.limits_mut().memory(128);
+    let release = builder.build().unwrap();
+    assert_eq!(release.executable, "cargo");
+    assert_eq!(release.args, vec!["build", "--release"]);
+    assert_eq!(release.current_dir.as_deref(), Some(".."));
+    assert_eq!(release.limits.memory, 128);
+
+    let same = CommandBuilder::from(command.clone()).build().unwrap();
+    assert_eq!(same, command);
+
+    let point = Point::builder()._0(1)._1(2).build();
+    let moved = point.to_builder()._1(5).build();
+    assert_eq!(moved, Point(1, 5));
+}
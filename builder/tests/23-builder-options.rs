@@ -0,0 +1,53 @@
+// The generated builder type can be renamed, given its own visibility and
+// extra derives, and its `build` method renamed, restricted or left out:
+//
+//     #[builder(name = "CmdBuilder", vis = "pub(crate)")]
+//     #[builder(derive(Debug, Clone, PartialEq))]
+//     #[builder(build_fn(name = "finish", vis = "pub(crate)"))]
+//
+// The error type follows the builder's name, here `CmdBuilderError`. With
+// `build_fn(skip)` no build method is generated so that one can be written by
+// hand next to the setters.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(name = "CmdBuilder", vis = "pub(crate)")]
+#[builder(derive(Debug, Clone, PartialEq))]
+#[builder(build_fn(name = "finish", vis = "pub(crate)"))]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable", derive(Clone), build_fn(skip))]
+pub struct Range {
+    start: u32,
+    end: u32,
+}
+
+impl RangeBuilder {
+    pub fn build(&self) -> Result<Range, RangeBuilderError> {
+        let start = self.start.ok_or(RangeBuilderError::MissingField { name: "start" })?;
+        let end = self.end.unwrap_or(start);
+        Ok(Range { start, end })
+    }
+}
+
+fn main() {
+    let mut builder: CmdBuilder = Command::builder();
+    builder.executable("cargo".to_owned());
+    let copy = builder.clone();
+    assert_eq!(builder, copy);
+    assert!(format!("{:?}", builder).contains("cargo"));
+
+    let command = builder.finish().unwrap();
+    assert_eq!(command.executable, "cargo");
+
+    let error: CmdBuilderError = Command::builder().finish().unwrap_err();
+    assert_eq!(error, CmdBuilderError::MissingField { name: "executable" });
+
+    let range = Range::builder().start(3).build().unwrap();
+    assert_eq!((range.start, range.end), (3, 3));
+}
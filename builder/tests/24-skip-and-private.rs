@@ -0,0 +1,54 @@
+// Fields marked #[builder(skip)] have no setter and no place in the builder;
+// `build` fills them with their `default = "..."` expression, or with Default.
+// Fields marked #[builder(private)] keep their setters, but those are private
+// to the module of the struct, so that only code next to it can set them.
+
+mod cache {
+    use derive_builder::Builder;
+    use std::collections::HashMap;
+    use std::marker::PhantomData;
+
+    #[derive(Builder)]
+    pub struct Cache<K> {
+        pub capacity: usize,
+        #[builder(private)]
+        pub(crate) shards: usize,
+        #[builder(skip)]
+        pub entries: HashMap<String, u32>,
+        #[builder(skip, default = "\"lru\".to_owned()")]
+        pub policy: String,
+        #[builder(skip)]
+        pub key: PhantomData<K>,
+    }
+
+    impl<K> CacheBuilder<K> {
+        pub fn single_shard(&mut self) -> &mut Self {
+            self.shards(1)
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(typestate)]
+    pub struct Id {
+        pub value: u64,
+        #[builder(skip)]
+        pub hits: u32,
+    }
+}
+
+use cache::{Cache, Id};
+
+fn main() {
+    let cache: Cache<u8> = Cache::builder()
+        .capacity(64)
+        .single_shard()
+        .build()
+        .unwrap();
+    assert_eq!(cache.capacity, 64);
+    assert_eq!(cache.shards, 1);
+    assert!(cache.entries.is_empty());
+    assert_eq!(cache.policy, "lru");
+
+    let id = Id::builder().value(7).build();
+    assert_eq!((id.value, id.hits), (7, 0));
+}
@@ -0,0 +1,58 @@
+// Fields marked #[builder(try_setter)] get a second setter, `try_<setter>`,
+// that takes anything convertible to the field's type with TryInto and returns
+// the conversion error instead of the builder when it fails:
+//
+//     impl ServerBuilder {
+//         pub fn try_port<V>(&mut self, port: V) -> Result<&mut Self, V::Error>
+//         where
+//             V: TryInto<NonZeroU16>,
+//         {...}
+//     }
+//
+// This lets a value parsed from user input be checked where it is passed in.
+
+use derive_builder::Builder;
+use std::net::SocketAddr;
+use std::num::NonZeroU16;
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(try_setter)]
+    port: NonZeroU16,
+    #[builder(try_setter, setter(name = "bind"))]
+    addr: Option<SocketAddr>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Limit {
+    #[builder(try_setter)]
+    max: u8,
+}
+
+struct Addr(&'static str);
+
+impl TryFrom<Addr> for SocketAddr {
+    type Error = std::net::AddrParseError;
+
+    fn try_from(addr: Addr) -> Result<Self, Self::Error> {
+        addr.0.parse()
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = Server::builder();
+    builder.try_port(8080u16)?.try_bind(Addr("127.0.0.1:8080"))?;
+    let server = builder.build()?;
+    assert_eq!(server.port.get(), 8080);
+    assert_eq!(server.addr, Some("127.0.0.1:8080".parse()?));
+
+    assert!(Server::builder().try_port(0u16).is_err());
+    assert!(Server::builder().try_bind(Addr("localhost")).is_err());
+
+    let limit = Limit::builder().try_max(200u32)?.build();
+    assert_eq!(limit.max, 200);
+    assert!(Limit::builder().try_max(300u32).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// With #[builder(no_std)] the generated code only refers to `core` and
+// `alloc`, so the derive can be used in `#![no_std]` crates that have an
+// allocator. The builder works as usual and `build` returns the same error
+// type, which implements `core::error::Error`.
+//
+// The crate has to declare `extern crate alloc;` for the error's String.
+
+#![no_std]
+
+extern crate alloc;
+// Only here so that the test binary gets a panic handler and `main`.
+extern crate std as _;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std)]
+pub struct Register {
+    name: String,
+    #[builder(each = "bit")]
+    bits: Vec<u8>,
+    #[builder(default = "0x1000")]
+    base: u32,
+    offset: Option<u32>,
+}
+
+#[derive(Builder)]
+#[builder(no_std, typestate)]
+pub struct Device {
+    #[builder(sub_builder)]
+    control: Register,
+}
+
+fn main() {
+    let mut builder = Register::builder();
+    builder.name("CTRL".to_string()).bit(0).bit(3);
+    let register = builder.build().unwrap();
+    assert_eq!(register.bits, [0, 3]);
+    assert_eq!(register.base, 0x1000);
+    assert_eq!(register.offset, None);
+
+    let error = Register::builder().build().unwrap_err();
+    assert_eq!(error, RegisterBuilderError::MissingField { name: "name" });
+    assert_eq!(error.to_string(), "field `name` is not set");
+    let _: &dyn core::error::Error = &error;
+
+    let error = match Device::builder().build() {
+        Ok(_) => unreachable!(),
+        Err(error) => error,
+    };
+    assert_eq!(error.field_path().as_deref(), Some("control.name"));
+}
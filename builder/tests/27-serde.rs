@@ -0,0 +1,77 @@
+// With #[builder(serde)] the builder derives serde's Serialize and Deserialize,
+// with every field optional: unset fields are skipped when serializing and may
+// be missing when deserializing. The builder also gets a `merge` method that
+// overlays the fields set in another builder,
+//
+//     impl ConfigBuilder {
+//         pub fn merge(&mut self, other: Self) -> &mut Self {...}
+//     }
+//
+// so configuration can be loaded in layers: `file.merge(env).merge(cli).build()`.
+// Sub-builders are merged field by field, anything else set in `other` simply
+// replaces the current value.
+//
+// The crate using this needs serde with its "derive" feature as a dependency.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(serde)]
+pub struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Builder, Debug)]
+#[builder(serde)]
+pub struct Config {
+    name: String,
+    #[builder(each = "feature")]
+    features: Vec<String>,
+    log_level: Option<String>,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+#[derive(Builder, Debug)]
+#[builder(serde, pattern = "owned")]
+pub struct Limits {
+    memory: u64,
+    cpus: u32,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file: ConfigBuilder = serde_json::from_str(
+        r#"{
+            "name": "app",
+            "features": ["metrics"],
+            "server": { "host": "0.0.0.0", "port": 80 }
+        }"#,
+    )?;
+    let env: ConfigBuilder = serde_json::from_str(r#"{ "server": { "port": 8080 } }"#)?;
+    let mut cli = Config::builder();
+    cli.log_level("debug".to_owned());
+
+    let config = file.merge(env).merge(cli).build()?;
+    assert_eq!(config.name, "app");
+    assert_eq!(config.features, ["metrics"]);
+    assert_eq!(config.log_level.as_deref(), Some("debug"));
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 8080);
+
+    let mut builder = Config::builder();
+    builder.name("app".to_owned());
+    builder.server_mut().port(80);
+    assert_eq!(
+        serde_json::to_string(&builder)?,
+        r#"{"name":"app","server":{"port":80}}"#,
+    );
+
+    let limits = Limits::builder()
+        .memory(512)
+        .merge(serde_json::from_str(r#"{ "cpus": 2 }"#)?)
+        .build()?;
+    assert_eq!((limits.memory, limits.cpus), (512, 2));
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// Sub-builders can use a different pattern from the builder holding them, and
+// can have their `build` renamed. The one thing an outer builder cannot hold is
+// a typestate builder, whose type changes as its fields are set.
+//
+// A builder that is not consumed by `build` builds a clone of each sub-builder,
+// so that it can be built again. Mutable and immutable builders are Clone
+// already; an owned one held by either needs #[builder(derive(Clone))].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned", derive(Clone))]
+pub struct Owned {
+    name: String,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "immutable")]
+pub struct Immutable {
+    port: u16,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(build_fn(name = "finish"))]
+pub struct Mutable {
+    retries: u8,
+}
+
+#[derive(Builder, Debug)]
+pub struct ByMutable {
+    #[builder(sub_builder)]
+    owned: Owned,
+    #[builder(sub_builder)]
+    immutable: Immutable,
+    #[builder(sub_builder)]
+    mutable: Mutable,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct ByOwned {
+    #[builder(sub_builder)]
+    owned: Owned,
+    #[builder(sub_builder)]
+    immutable: Immutable,
+    #[builder(sub_builder)]
+    mutable: Mutable,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "immutable")]
+pub struct ByImmutable {
+    #[builder(sub_builder)]
+    immutable: Immutable,
+    #[builder(sub_builder)]
+    mutable: Mutable,
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate)]
+pub struct ByTypestate {
+    id: u32,
+    #[builder(sub_builder)]
+    owned: Owned,
+}
+
+fn main() {
+    let mut builder = ByMutable::builder();
+    *builder.owned_mut() = Owned::builder().name("a".to_owned());
+    *builder.immutable_mut() = builder.immutable_mut().port(80);
+    builder.mutable_mut().retries(3);
+    let built = builder.build().unwrap();
+    assert_eq!(built.owned.name, "a");
+    assert_eq!(built.immutable.port, 80);
+    assert_eq!(built.mutable.retries, 3);
+    let again = builder.build().unwrap();
+    assert_eq!(again.owned, built.owned);
+    assert_eq!(again.mutable, built.mutable);
+
+    let mut builder = ByOwned::builder();
+    *builder.owned_mut() = Owned::builder().name("b".to_owned());
+    *builder.immutable_mut() = builder.immutable_mut().port(443);
+    builder.mutable_mut().retries(1);
+    let built = builder.build().unwrap();
+    assert_eq!(built.owned.name, "b");
+    assert_eq!(built.immutable.port, 443);
+    assert_eq!(built.mutable.retries, 1);
+
+    let mut builder = ByImmutable::builder();
+    *builder.immutable_mut() = builder.immutable_mut().port(8080);
+    builder.mutable_mut().retries(5);
+    let built = builder.build().unwrap();
+    assert_eq!(built.immutable.port, 8080);
+    assert_eq!(built.mutable.retries, 5);
+    assert_eq!(builder.build().unwrap().mutable.retries, 5);
+
+    let mut builder = ByTypestate::builder().id(7);
+    *builder.owned_mut() = Owned::builder().name("c".to_owned());
+    let built = builder.build().unwrap();
+    assert_eq!(built.id, 7);
+    assert_eq!(built.owned.name, "c");
+}
@@ -0,0 +1,19 @@
+// The hidden methods through which an outer builder builds and merges its
+// sub-builders are exactly as visible as `build`, so they cannot be used to
+// get around `build_fn(vis = "...")`.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(build_fn(vis = "pub(self)"))]
+    pub struct Config {
+        pub name: String,
+    }
+}
+
+fn main() {
+    let mut builder = config::Config::builder();
+    builder.name("prod".to_owned());
+    let _config = builder.__build();
+}
@@ -1,13 +1,33 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-create-builder.rs");
-    //t.pass("tests/03-call-setters.rs");
-    //t.pass("tests/04-call-build.rs");
-    //t.pass("tests/05-method-chaining.rs");
-    //t.pass("tests/06-optional-field.rs");
-    //t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
-    //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-create-builder.rs");
+    t.pass("tests/03-call-setters.rs");
+    t.pass("tests/04-call-build.rs");
+    t.pass("tests/05-method-chaining.rs");
+    t.pass("tests/06-optional-field.rs");
+    t.pass("tests/07-repeated-field.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typestate.rs");
+    t.compile_fail("tests/11-typestate-missing-field.rs");
+    t.pass("tests/12-default.rs");
+    t.pass("tests/13-error-enum.rs");
+    t.pass("tests/14-validate.rs");
+    t.pass("tests/15-setter-options.rs");
+    t.pass("tests/16-pattern.rs");
+    t.pass("tests/17-generics.rs");
+    t.pass("tests/18-tuple-and-enum.rs");
+    t.compile_fail("tests/19-union.rs");
+    t.pass("tests/20-each-collections.rs");
+    t.pass("tests/21-sub-builder.rs");
+    t.pass("tests/22-to-builder.rs");
+    t.pass("tests/23-builder-options.rs");
+    t.pass("tests/24-skip-and-private.rs");
+    t.pass("tests/25-try-setter.rs");
+    t.pass("tests/26-no-std.rs");
+    t.pass("tests/27-serde.rs");
+    t.pass("tests/28-sub-builder-patterns.rs");
+    t.compile_fail("tests/29-private-build.rs");
 }
@@ -0,0 +1,139 @@
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Error, Expr, ExprLit, ExprPath, Lit, LitInt, LitStr, Meta, Result, Token,
+    WherePredicate,
+};
+
+#[derive(Default)]
+pub struct ContainerAttrs {
+    // `debug(bound = "...")`, replacing the inferred bounds entirely.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    // Print without any whitespace, as `Field{name:"F",bitmask:28}`.
+    pub compact: bool,
+    // End the fields with `..`, as `finish_non_exhaustive` does.
+    pub non_exhaustive: bool,
+}
+
+#[derive(Default)]
+pub struct FieldAttrs {
+    // `#[debug = "..."]`, a format string applied to the field's value.
+    pub format: Option<LitStr>,
+    // Leave the field out entirely.
+    pub skip: bool,
+    pub redact: Option<Redact>,
+    // The name printed instead of the field's own.
+    pub rename: Option<LitStr>,
+    // `debug(with = "...")`, a function printing the field in place of Debug.
+    pub with: Option<ExprPath>,
+    // `debug(limit = N)`, printing at most N of the field's elements.
+    pub limit: Option<usize>,
+    // `debug(bound = "...")`, replacing only the bounds inferred from this
+    // field's type.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+}
+
+// `debug(redact)` or `debug(redact(len))`.
+pub struct Redact {
+    // Show the value's `len()`, for telling an empty secret from a set one.
+    pub len: bool,
+}
+
+pub fn container_attrs(attrs: &[Attribute]) -> Result<ContainerAttrs> {
+    let mut parsed = ContainerAttrs::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                parsed.bound = Some(bound(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("compact") {
+                parsed.compact = true;
+                Ok(())
+            } else if meta.path.is_ident("non_exhaustive") {
+                parsed.non_exhaustive = true;
+                Ok(())
+            } else {
+                Err(meta.error("unrecognized debug attribute"))
+            }
+        })?;
+    }
+
+    Ok(parsed)
+}
+
+pub fn field_attrs(attrs: &[Attribute]) -> Result<FieldAttrs> {
+    let mut parsed = FieldAttrs::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => parsed.format = Some(lit.clone()),
+                value => {
+                    return Err(Error::new_spanned(
+                        value,
+                        "expected a format string, as in `#[debug = \"{:?}\"]`",
+                    ))
+                }
+            },
+            Meta::List(_) => attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("redact") {
+                    let mut len = false;
+                    if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("len") {
+                                len = true;
+                                Ok(())
+                            } else {
+                                Err(meta.error("unrecognized redact option"))
+                            }
+                        })?;
+                    }
+                    parsed.redact = Some(Redact { len });
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    parsed.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    parsed.bound = Some(bound(&meta)?);
+                    Ok(())
+                } else if meta.path.is_ident("limit") {
+                    let limit: LitInt = meta.value()?.parse()?;
+                    parsed.limit = Some(limit.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    parsed.with = Some(lit.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unrecognized debug attribute"))
+                }
+            })?,
+            Meta::Path(_) => {
+                return Err(Error::new_spanned(
+                    &attr.meta,
+                    "expected `#[debug = \"...\"]` or `#[debug(...)]`",
+                ))
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+// `bound = "..."`, where an empty string stands for no bounds at all.
+fn bound(meta: &ParseNestedMeta) -> Result<Punctuated<WherePredicate, Token![,]>> {
+    let lit: LitStr = meta.value()?.parse()?;
+    lit.parse_with(Punctuated::parse_terminated)
+}
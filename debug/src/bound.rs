@@ -0,0 +1,122 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, Generics, Ident, Type, TypeBareFn, TypePath, TypePtr, TypeTraitObject,
+    WherePredicate,
+};
+
+// Types whose formatting impls hold whatever their type arguments are, like
+// `impl<T: ?Sized> Debug for PhantomData<T>`.
+const OPAQUE: &[&str] = &["PhantomData", "Weak", "Discriminant"];
+
+// The bounds needed to format fields of the given types with `bound`, such as
+// Debug. A derive cannot look up the impls of the types it sees, so this goes
+// by what they usually are:
+//
+//   - `T: Debug` for every type parameter that appears as an argument of a
+//     field's type, like in `Vec<Option<T>>` or `Rc<RefCell<T>>`, or behind a
+//     reference;
+//   - `T::Value: Debug` and `<T as Trait>::Value: Debug` for associated types
+//     of a type parameter, rather than a bound on the parameter itself;
+//   - nothing for what is mentioned only inside function pointers, raw
+//     pointers, PhantomData and the like, which are formatted the same way for
+//     any type argument, nor inside trait objects, which are Debug through a
+//     supertrait whatever their arguments are or not at all. A bound on
+//     `dyn Trait<T>` would also mean `dyn Trait<T> + 'static`, which borrowed
+//     trait objects are not.
+//
+// Bounding whole field types instead would not work for recursive types, as
+// explained in tests/06-bound-trouble.rs.
+pub fn infer<'a>(
+    generics: &Generics,
+    tys: impl IntoIterator<Item = &'a Type>,
+    bound: &TokenStream,
+) -> Vec<WherePredicate> {
+    let params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    let mut visitor = Visitor {
+        params: &params,
+        used: Vec::new(),
+        bounded: Vec::new(),
+    };
+    for ty in tys {
+        visitor.visit_type(ty);
+    }
+
+    let mut bounds: Vec<TokenStream> = params
+        .iter()
+        .filter(|param| visitor.used.contains(param))
+        .map(|param| param.to_token_stream())
+        .collect();
+    let mut seen = Vec::new();
+    for ty in visitor.bounded {
+        let key = ty.to_string();
+        if !seen.contains(&key) {
+            seen.push(key);
+            bounds.push(ty);
+        }
+    }
+
+    bounds
+        .into_iter()
+        .map(|ty| parse_quote!(#ty: #bound))
+        .collect()
+}
+
+struct Visitor<'a> {
+    params: &'a [&'a Ident],
+    // Type parameters that need the bound themselves.
+    used: Vec<&'a Ident>,
+    // Associated types that need the bound.
+    bounded: Vec<TokenStream>,
+}
+
+impl Visitor<'_> {
+    // Whether a type parameter appears anywhere in `ty`.
+    fn mentions<T: ToTokens>(&self, ty: &T) -> bool {
+        fn walk(params: &[&Ident], tokens: TokenStream) -> bool {
+            tokens.into_iter().any(|token| match token {
+                TokenTree::Ident(ident) => params.iter().any(|param| **param == ident),
+                TokenTree::Group(group) => walk(params, group.stream()),
+                TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+            })
+        }
+        walk(self.params, ty.to_token_stream())
+    }
+}
+
+impl Visit<'_> for Visitor<'_> {
+    fn visit_type_path(&mut self, ty: &TypePath) {
+        if let Some(qself) = &ty.qself {
+            if self.mentions(&qself.ty) {
+                self.bounded.push(ty.to_token_stream());
+                return;
+            }
+        }
+        let segments = &ty.path.segments;
+        if segments
+            .last()
+            .is_some_and(|segment| OPAQUE.iter().any(|opaque| segment.ident == opaque))
+        {
+            return;
+        }
+        if ty.qself.is_none() && ty.path.leading_colon.is_none() {
+            let first = &segments[0].ident;
+            if let Some(param) = self.params.iter().copied().find(|param| *param == first) {
+                if segments.len() > 1 {
+                    self.bounded.push(ty.to_token_stream());
+                } else if !self.used.contains(&param) {
+                    self.used.push(param);
+                }
+                return;
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_type_bare_fn(&mut self, _: &TypeBareFn) {}
+
+    fn visit_type_ptr(&mut self, _: &TypePtr) {}
+
+    fn visit_type_trait_object(&mut self, _: &TypeTraitObject) {}
+}
@@ -0,0 +1,405 @@
+use crate::bound;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::ops::Range;
+use syn::ext::IdentExt;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Member, Result, Type};
+
+// A struct or enum variant with the template it is displayed with.
+struct Shape<'a> {
+    // `Self` or `Self::Variant`, for matching on it.
+    path: TokenStream,
+    fields: &'a Fields,
+    template: Template<'a>,
+}
+
+// A `#[display("...")]` template rewritten into a format string over the
+// bindings `__0`, `__1`, ... of the fields it mentions.
+struct Template<'a> {
+    format: LitStr,
+    // Indices of the fields used.
+    used: Vec<usize>,
+    // The type of each field used with the trait it is formatted with, once
+    // for every trait.
+    bounds: Vec<(&'a Type, TokenStream)>,
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+
+    let shapes = match &input.data {
+        Data::Struct(data) => {
+            let Some(template) = template_attr(&input.attrs)? else {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "expected #[display(\"...\")] on the struct",
+                ));
+            };
+            vec![Shape {
+                path: quote!(Self),
+                fields: &data.fields,
+                template: Template::parse(&template, &data.fields)?,
+            }]
+        }
+        Data::Enum(data) => {
+            // A template on the enum itself is used for the variants without
+            // one of their own.
+            let fallback = template_attr(&input.attrs)?;
+            data.variants
+                .iter()
+                .map(|variant| {
+                    let name = &variant.ident;
+                    // Unit variants display their name unless told otherwise.
+                    let template = match template_attr(&variant.attrs)?.or_else(|| fallback.clone())
+                    {
+                        Some(template) => template,
+                        None if matches!(variant.fields, Fields::Unit) => {
+                            LitStr::new(&name.unraw().to_string(), name.span())
+                        }
+                        None => {
+                            return Err(Error::new_spanned(
+                                variant,
+                                "expected #[display(\"...\")] on the variant or the enum",
+                            ))
+                        }
+                    };
+                    Ok(Shape {
+                        path: quote!(Self::#name),
+                        fields: &variant.fields,
+                        template: Template::parse(&template, &variant.fields)?,
+                    })
+                })
+                .collect::<Result<_>>()?
+        }
+        Data::Union(data) => {
+            let union_token = data.union_token;
+            return Err(Error::new_spanned(
+                quote!(#union_token #ident),
+                "CustomDisplay cannot be derived for unions",
+            ));
+        }
+    };
+
+    // Each field only needs the trait it is formatted with.
+    let mut traits: Vec<(String, &TokenStream)> = Vec::new();
+    for (_, bound) in shapes.iter().flat_map(|shape| &shape.template.bounds) {
+        let key = bound.to_string();
+        if !traits.iter().any(|(seen, _)| *seen == key) {
+            traits.push((key, bound));
+        }
+    }
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    for (key, bound) in traits {
+        let tys = shapes.iter().flat_map(|shape| {
+            shape
+                .template
+                .bounds
+                .iter()
+                .filter(|(_, bound)| bound.to_string() == key)
+                .map(|(ty, _)| *ty)
+        });
+        predicates.extend(bound::infer(&input.generics, tys, bound));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let arms = shapes.iter().map(Shape::arm);
+    // An enum without variants has no value to match on.
+    let body = if shapes.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+fn template_attr(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    let mut template = None;
+    for attr in attrs {
+        if attr.path().is_ident("display") {
+            if template.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[display] attribute"));
+            }
+            template = Some(attr.parse_args()?);
+        }
+    }
+    Ok(template)
+}
+
+impl Shape<'_> {
+    // `Self::Variant { name: __0, .. } => ::core::write!(formatter, ...),`
+    fn arm(&self) -> TokenStream {
+        let path = &self.path;
+        let format = &self.template.format;
+        let bindings: Vec<Ident> = self.template.used.iter().copied().map(binding).collect();
+        let pattern = match self.fields {
+            Fields::Named(fields) => {
+                let members = self.template.used.iter().map(|index| {
+                    let ident = fields.named[*index].ident.as_ref().unwrap();
+                    Member::Named(ident.clone())
+                });
+                quote!(#path { #(#members: #bindings,)* .. })
+            }
+            Fields::Unnamed(fields) => {
+                let elems = (0..fields.unnamed.len()).map(|index| {
+                    if self.template.used.contains(&index) {
+                        let binding = binding(index);
+                        quote!(#binding)
+                    } else {
+                        quote!(_)
+                    }
+                });
+                quote!(#path(#(#elems),*))
+            }
+            Fields::Unit => quote!(#path),
+        };
+        quote! {
+            #pattern => ::core::write!(formatter, #format #(, #bindings = #bindings)*),
+        }
+    }
+}
+
+impl<'a> Template<'a> {
+    fn parse(lit: &LitStr, fields: &'a Fields) -> Result<Self> {
+        let value = lit.value();
+        let mut format = String::new();
+        let mut used = Vec::new();
+        let mut bounds: Vec<(&Type, TokenStream)> = Vec::new();
+
+        let mut chars = value.char_indices().peekable();
+        while let Some((start, ch)) = chars.next() {
+            match ch {
+                '{' if chars.next_if(|(_, next)| *next == '{').is_some() => format.push_str("{{"),
+                '}' if chars.next_if(|(_, next)| *next == '}').is_some() => format.push_str("}}"),
+                '{' => {
+                    let mut end = None;
+                    for (index, ch) in chars.by_ref() {
+                        if ch == '}' {
+                            end = Some(index);
+                            break;
+                        }
+                    }
+                    let Some(end) = end else {
+                        return Err(Placeholder {
+                            lit,
+                            range: start..value.len(),
+                        }
+                        .error("unterminated placeholder, use `{{` for a literal brace"));
+                    };
+                    let at = Placeholder {
+                        lit,
+                        range: start..end + 1,
+                    };
+                    let placeholder = &value[start + 1..end];
+                    let (name, spec) = match placeholder.split_once(':') {
+                        Some((name, spec)) => (name, Some(spec)),
+                        None => (placeholder, None),
+                    };
+                    let (index, ty) = field(fields, name, &at)?;
+                    if !used.contains(&index) {
+                        used.push(index);
+                    }
+                    let spec = match spec {
+                        Some(spec) => Some(Spec::parse(spec, fields, &at, &mut used)?),
+                        None => None,
+                    };
+                    let bound = fmt_trait(spec.as_ref().map_or("", |spec| spec.ty));
+                    if !bounds.iter().any(|(seen, seen_bound)| {
+                        std::ptr::eq(*seen, ty) && seen_bound.to_string() == bound.to_string()
+                    }) {
+                        bounds.push((ty, bound));
+                    }
+                    format.push('{');
+                    format.push_str(&binding(index).to_string());
+                    if let Some(spec) = spec {
+                        format.push(':');
+                        format.push_str(&spec.format);
+                    }
+                    format.push('}');
+                }
+                '}' => {
+                    return Err(Placeholder {
+                        lit,
+                        range: start..start + 1,
+                    }
+                    .error("unmatched `}`, use `}}` for a literal brace"))
+                }
+                ch => format.push(ch),
+            }
+        }
+
+        Ok(Template {
+            format: LitStr::new(&format, lit.span()),
+            used,
+            bounds,
+        })
+    }
+}
+
+// The field a placeholder names: `{name}` for a named field, `{0}` for a tuple
+// field.
+fn field<'a>(fields: &'a Fields, name: &str, at: &Placeholder) -> Result<(usize, &'a Type)> {
+    if name.is_empty() {
+        return Err(at.error("placeholders must name a field, as in `{name}` or `{0}`"));
+    }
+    let found = fields
+        .iter()
+        .enumerate()
+        .find(|(index, field)| match &field.ident {
+            Some(ident) => ident.unraw() == name || *ident == name,
+            None => index.to_string() == name,
+        });
+    match found {
+        Some((index, field)) => Ok((index, &field.ty)),
+        None => Err(at.error(&format!("no field `{}` to display", name))),
+    }
+}
+
+// A placeholder's format spec, `[[fill]align][sign]['#']['0'][width]['.'
+// precision][type]`, with the fields named in its width and precision, as in
+// `{name:>width$}`, rewritten to their bindings.
+struct Spec<'a> {
+    format: String,
+    // The type at the end, such as `?` or `x`.
+    ty: &'a str,
+}
+
+impl<'a> Spec<'a> {
+    fn parse(
+        spec: &'a str,
+        fields: &Fields,
+        at: &Placeholder,
+        used: &mut Vec<usize>,
+    ) -> Result<Self> {
+        let mut format = String::new();
+        let mut rest = spec;
+
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(fill), Some('<' | '^' | '>')) => {
+                take(&mut rest, fill.len_utf8() + 1, &mut format)
+            }
+            (Some('<' | '^' | '>'), _) => take(&mut rest, 1, &mut format),
+            _ => {}
+        }
+        if rest.starts_with(['+', '-']) {
+            take(&mut rest, 1, &mut format);
+        }
+        if rest.starts_with('#') {
+            take(&mut rest, 1, &mut format);
+        }
+        // As opposed to `0$`, a width taken from field 0.
+        if rest.starts_with('0') && !rest.starts_with("0$") {
+            take(&mut rest, 1, &mut format);
+        }
+        count(&mut rest, &mut format, fields, at, used)?;
+        if let Some(precision) = rest.strip_prefix('.') {
+            format.push('.');
+            rest = precision;
+            if rest.starts_with('*') {
+                return Err(at.error(
+                    "`.*` is not supported, name the field holding the precision as in `{value:.precision$}`",
+                ));
+            }
+            count(&mut rest, &mut format, fields, at, used)?;
+        }
+        format.push_str(rest);
+
+        Ok(Spec { format, ty: rest })
+    }
+}
+
+// Moves the first `len` bytes of the spec over to the rewritten one.
+fn take(rest: &mut &str, len: usize, format: &mut String) {
+    format.push_str(&rest[..len]);
+    *rest = &rest[len..];
+}
+
+// A width or precision: either a number, or a field followed by `$`.
+fn count(
+    rest: &mut &str,
+    format: &mut String,
+    fields: &Fields,
+    at: &Placeholder,
+    used: &mut Vec<usize>,
+) -> Result<()> {
+    let len = rest
+        .find(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .unwrap_or(rest.len());
+    if rest[len..].starts_with('$') {
+        let (index, _) = field(fields, &rest[..len], at)?;
+        if !used.contains(&index) {
+            used.push(index);
+        }
+        format.push_str(&binding(index).to_string());
+        format.push('$');
+        *rest = &rest[len + 1..];
+    } else {
+        let len = rest
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(rest.len());
+        take(rest, len, format);
+    }
+    Ok(())
+}
+
+// The formatting trait the type of a format spec, such as `x` or `?`, uses.
+fn fmt_trait(ty: &str) -> TokenStream {
+    if ty.ends_with('?') {
+        return quote!(::core::fmt::Debug);
+    }
+    match ty {
+        "x" => quote!(::core::fmt::LowerHex),
+        "X" => quote!(::core::fmt::UpperHex),
+        "o" => quote!(::core::fmt::Octal),
+        "b" => quote!(::core::fmt::Binary),
+        "e" => quote!(::core::fmt::LowerExp),
+        "E" => quote!(::core::fmt::UpperExp),
+        _ => quote!(::core::fmt::Display),
+    }
+}
+
+fn binding(index: usize) -> Ident {
+    format_ident!("__{}", index)
+}
+
+// The part of a template, such as a placeholder, that an error is about.
+struct Placeholder<'a> {
+    lit: &'a LitStr,
+    range: Range<usize>,
+}
+
+impl Placeholder<'_> {
+    // Points into the string literal where the compiler allows it. Only nightly
+    // compilers can point into a literal, so on stable the error is on the whole
+    // template instead and the message says which part of it is meant.
+    fn error(&self, message: &str) -> Error {
+        let value = self.lit.value();
+        let token = self.lit.token();
+        if token.to_string() == format!("\"{}\"", value) {
+            if let Some(span) = token.subspan(self.range.start + 1..self.range.end + 1) {
+                return Error::new(span, message);
+            }
+        }
+        Error::new(
+            self.lit.span(),
+            format!(
+                "{}, at `{}` (byte {} of the template)",
+                message,
+                &value[self.range.clone()],
+                self.range.start,
+            ),
+        )
+    }
+}
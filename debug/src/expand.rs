@@ -0,0 +1,345 @@
+use crate::attr::{self, FieldAttrs};
+use crate::bound;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Member, Result, Token, Type, WherePredicate};
+
+// A struct or enum variant, printed the way std's derive would print it.
+pub struct Shape<'a> {
+    // The name printed for it.
+    pub name: String,
+    // `Self` or `Self::Variant`, for matching on it.
+    pub path: TokenStream,
+    pub fields: &'a Fields,
+    pub parsed: Vec<Field<'a>>,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    // The variable the field is bound to when matching, `__0` etc. so that it
+    // cannot collide with anything in a format string.
+    pub binding: Ident,
+    pub ty: &'a Type,
+    pub attrs: FieldAttrs,
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = attr::container_attrs(&input.attrs)?;
+    let ident = &input.ident;
+    let shapes = shapes(input, "CustomDebug")?;
+
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds(input, attrs.bound, &shapes));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let arms = shapes.iter().map(|shape| {
+        if attrs.compact {
+            shape.compact_arm(attrs.non_exhaustive)
+        } else {
+            shape.arm(attrs.non_exhaustive)
+        }
+    });
+    let with = with_helper(&shapes);
+    // An enum without variants has no value to match on.
+    let body = if shapes.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #with
+                #body
+            }
+        }
+    })
+}
+
+// The struct, or each variant of the enum, with its `#[debug]` attributes
+// parsed. `derive` names the macro in errors.
+pub fn shapes<'a>(input: &'a DeriveInput, derive: &str) -> Result<Vec<Shape<'a>>> {
+    let ident = &input.ident;
+    match &input.data {
+        Data::Struct(data) => Ok(vec![Shape::parse(ident, quote!(Self), &data.fields)?]),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let name = &variant.ident;
+                Shape::parse(name, quote!(Self::#name), &variant.fields)
+            })
+            .collect(),
+        Data::Union(data) => {
+            let union_token = data.union_token;
+            Err(Error::new_spanned(
+                quote!(#union_token #ident),
+                format!("{} cannot be derived for unions", derive),
+            ))
+        }
+    }
+}
+
+// The where clause predicates for printing the fields: the container's
+// `debug(bound = "...")` if there is one, or else what is inferred from the
+// fields printed through Debug plus every field-level bound.
+pub fn bounds(
+    input: &DeriveInput,
+    bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    shapes: &[Shape],
+) -> Vec<WherePredicate> {
+    if let Some(bound) = bound {
+        return bound.into_iter().collect();
+    }
+    let fields = shapes.iter().flat_map(|shape| &shape.parsed);
+    let mut bounds = bound::infer(
+        &input.generics,
+        fields
+            .clone()
+            .filter(|field| field.is_debug() && field.attrs.bound.is_none())
+            .map(|field| field.ty),
+        &quote!(::core::fmt::Debug),
+    );
+    bounds.extend(
+        fields
+            .filter_map(|field| field.attrs.bound.clone())
+            .flatten(),
+    );
+    bounds
+}
+
+// Wraps a call to the function of a `debug(with = "...")` field, or the loop
+// printing a `debug(limit = N)` one. Only emitted when some field needs it.
+pub fn with_helper(shapes: &[Shape]) -> Option<TokenStream> {
+    shapes
+        .iter()
+        .flat_map(|shape| &shape.parsed)
+        .any(|field| field.attrs.with.is_some() || field.attrs.limit.is_some())
+        .then(|| {
+            quote! {
+                struct __DebugWith<F>(F)
+                where
+                    F: ::core::ops::Fn(&mut ::core::fmt::Formatter) -> ::core::fmt::Result;
+
+                impl<F> ::core::fmt::Debug for __DebugWith<F>
+                where
+                    F: ::core::ops::Fn(&mut ::core::fmt::Formatter) -> ::core::fmt::Result,
+                {
+                    fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        (self.0)(formatter)
+                    }
+                }
+            }
+        })
+}
+
+impl<'a> Shape<'a> {
+    fn parse(ident: &Ident, path: TokenStream, fields: &'a Fields) -> Result<Self> {
+        let parsed = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let attrs = attr::field_attrs(&field.attrs)?;
+                let shown = [
+                    attrs.skip,
+                    attrs.format.is_some(),
+                    attrs.redact.is_some(),
+                    attrs.with.is_some(),
+                    attrs.limit.is_some(),
+                ];
+                if shown.iter().filter(|set| **set).count() > 1 {
+                    return Err(Error::new_spanned(
+                        field,
+                        "`skip`, `redact`, `with`, `limit` and a format string are mutually exclusive",
+                    ));
+                }
+                if let (Some(rename), None) = (&attrs.rename, &field.ident) {
+                    return Err(Error::new_spanned(
+                        rename,
+                        "`rename` only applies to named fields",
+                    ));
+                }
+                Ok(Field {
+                    member: match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(index.into()),
+                    },
+                    binding: format_ident!("__{}", index),
+                    ty: &field.ty,
+                    attrs,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Shape {
+            name: ident.unraw().to_string(),
+            path,
+            fields,
+            parsed,
+        })
+    }
+
+    // `Self::Variant { name: __0, .. } => formatter.debug_struct(...)...`
+    fn arm(&self, non_exhaustive: bool) -> TokenStream {
+        let pattern = self.pattern();
+        let name = &self.name;
+        let shown = self.shown();
+        let values = shown.iter().map(|field| field.value());
+        let finish = if non_exhaustive {
+            quote!(finish_non_exhaustive)
+        } else {
+            quote!(finish)
+        };
+        match self.fields {
+            Fields::Named(_) => {
+                let names = shown.iter().map(|field| field.name());
+                quote! {
+                    #pattern => formatter
+                        .debug_struct(#name)
+                        #(.field(#names, #values))*
+                        .#finish(),
+                }
+            }
+            Fields::Unnamed(_) => quote! {
+                #pattern => formatter
+                    .debug_tuple(#name)
+                    #(.field(#values))*
+                    .#finish(),
+            },
+            Fields::Unit => quote! {
+                #pattern => formatter.write_str(#name),
+            },
+        }
+    }
+
+    // `debug(compact)`: the same as `arm` without the whitespace that
+    // DebugStruct and DebugTuple put in, writing `Variant{name:` and so on
+    // before each value.
+    fn compact_arm(&self, non_exhaustive: bool) -> TokenStream {
+        let pattern = self.pattern();
+        let shown = self.shown();
+        let (open, close) = match self.fields {
+            Fields::Named(_) => ("{", "}"),
+            Fields::Unnamed(_) => ("(", ")"),
+            Fields::Unit => ("", ""),
+        };
+
+        let mut prefix = self.name.clone();
+        let mut writes = Vec::new();
+        for (index, field) in shown.iter().enumerate() {
+            prefix.push_str(if index == 0 { open } else { "," });
+            if let Fields::Named(_) = self.fields {
+                prefix.push_str(&field.name());
+                prefix.push(':');
+            }
+            let value = field.value();
+            // Written without the formatter's flags, so that `{:#?}` does
+            // not spread a nested value over several lines either.
+            writes.push(quote! {
+                formatter.write_str(#prefix)?;
+                ::core::write!(formatter, "{:?}", #value)?;
+            });
+            prefix.clear();
+        }
+        let non_exhaustive = non_exhaustive && !matches!(self.fields, Fields::Unit);
+        if non_exhaustive {
+            prefix.push_str(if shown.is_empty() { open } else { "," });
+            prefix.push_str("..");
+        }
+        if !shown.is_empty() || non_exhaustive {
+            prefix.push_str(close);
+        }
+
+        quote! {
+            #pattern => {
+                #(#writes)*
+                formatter.write_str(#prefix)
+            }
+        }
+    }
+
+    // `Self::Variant { name: __0, .. }`, binding every field that is printed.
+    pub fn pattern(&self) -> TokenStream {
+        let path = &self.path;
+        let members = self.parsed.iter().map(|field| &field.member);
+        let bindings = self.parsed.iter().map(|field| {
+            if field.attrs.skip {
+                quote!(_)
+            } else {
+                let binding = &field.binding;
+                quote!(#binding)
+            }
+        });
+        match self.fields {
+            Fields::Named(_) => quote!(#path { #(#members: #bindings),* }),
+            Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+            Fields::Unit => quote!(#path),
+        }
+    }
+
+    pub fn shown(&self) -> Vec<&Field<'_>> {
+        self.parsed
+            .iter()
+            .filter(|field| !field.attrs.skip)
+            .collect()
+    }
+}
+
+impl Field<'_> {
+    // Whether the field's own Debug impl is used to print it.
+    pub fn is_debug(&self) -> bool {
+        !self.attrs.skip && self.attrs.redact.is_none() && self.attrs.with.is_none()
+    }
+
+    pub fn name(&self) -> String {
+        if let Some(rename) = &self.attrs.rename {
+            return rename.value();
+        }
+        match &self.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+
+    // The `&dyn Debug` printed for the field.
+    pub fn value(&self) -> TokenStream {
+        let binding = &self.binding;
+        if let Some(redact) = &self.attrs.redact {
+            return if redact.len {
+                quote!(&::core::format_args!("\"<redacted len={}>\"", #binding.len()))
+            } else {
+                quote!(&"<redacted>")
+            };
+        }
+        if let Some(with) = &self.attrs.with {
+            return quote!(&__DebugWith(|formatter| (#with)(#binding, formatter)));
+        }
+        if let Some(limit) = &self.attrs.limit {
+            return quote! {
+                &__DebugWith(|formatter| {
+                    let mut iter = #binding.into_iter();
+                    let mut list = formatter.debug_list();
+                    list.entries(iter.by_ref().take(#limit));
+                    let rest = iter.count();
+                    if rest > 0 {
+                        list.entry(&::core::format_args!("... ({} more)", rest));
+                    }
+                    list.finish()
+                })
+            };
+        }
+        match &self.attrs.format {
+            Some(format) => quote!(&::core::format_args!(#format, #binding)),
+            None => quote!(&#binding),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::attr;
+use crate::expand::{self, Shape};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Result};
+
+// `log_fields` hands every printed field to the visitor as a name and a
+// `&dyn Debug`, going by the same `#[debug]` attributes as CustomDebug. A
+// proc-macro crate cannot export the visitor trait, so the method names
+// whatever `FieldVisitor` is in scope where the derive is used, which has to
+// provide `fn field(&mut self, name: &str, value: &dyn Debug)`.
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = attr::container_attrs(&input.attrs)?;
+    let ident = &input.ident;
+    let shapes = expand::shapes(input, "LogFields")?;
+
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(expand::bounds(input, attrs.bound, &shapes));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let variant = matches!(input.data, Data::Enum(_));
+    let arms = shapes.iter().map(|shape| arm(shape, variant));
+    let with = expand::with_helper(&shapes);
+    // An enum without variants has no value to match on.
+    let body = if shapes.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn log_fields(&self, visitor: &mut impl FieldVisitor) {
+                #with
+                #body
+            }
+        }
+    })
+}
+
+// `Self::Variant { name: __0, .. } => { visitor.field("name", ...); }`, with
+// the variant of an enum given first as a field of its own so that variants
+// with the same fields can be told apart.
+fn arm(shape: &Shape, variant: bool) -> TokenStream {
+    let pattern = shape.pattern();
+    let shown = shape.shown();
+    let names = shown.iter().map(|field| field.name());
+    let values = shown.iter().map(|field| field.value());
+    let variant = variant.then(|| {
+        let name = &shape.name;
+        quote!(visitor.field("variant", &#name);)
+    });
+    quote! {
+        #pattern => {
+            #variant
+            #(visitor.field(#names, #values);)*
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// CustomDebug also works on enums, printing each variant the way std's derive
+// would: struct variants as `Variant { field: value }`, tuple variants as
+// `Variant(value)` and unit variants by name alone. `#[debug = "..."]` applies
+// to the fields of any variant.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub enum Error {
+    Io {
+        path: &'static str,
+        #[debug = "{:#06x}"]
+        code: u16,
+    },
+    Status(#[debug = "0x{:x}"] u32, &'static str),
+    Timeout,
+}
+
+#[derive(CustomDebug)]
+pub enum Slot<T> {
+    Full(T),
+    Empty(PhantomData<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let io = Error::Io {
+        path: "/dev/null",
+        code: 0x1f,
+    };
+    assert_eq!(format!("{:?}", io), r#"Io { path: "/dev/null", code: 0x001f }"#);
+
+    let status = Error::Status(0xdead, "gone");
+    assert_eq!(format!("{:?}", status), r#"Status(0xdead, "gone")"#);
+
+    assert_eq!(format!("{:?}", Error::Timeout), "Timeout");
+
+    assert_eq!(format!("{:?}", Slot::Full(3)), "Full(3)");
+    assert_eq!(
+        format!("{:?}", Slot::<u8>::Empty(PhantomData)),
+        "Empty(PhantomData<u8>)",
+    );
+
+    assert_debug::<Never>();
+}
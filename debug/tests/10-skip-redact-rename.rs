@@ -0,0 +1,61 @@
+// Fields can be left out of the output with #[debug(skip)], hidden behind a
+// placeholder with #[debug(redact)], and printed under another name with
+// #[debug(rename = "...")]. `redact(len)` also shows the length of the hidden
+// value, which only needs a `len()` method:
+//
+//     Login { user: "ferris", password: "<redacted len=7>", token: "<redacted>" }
+//
+// Neither skipped nor redacted fields need their type to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub struct Token(u64);
+
+#[derive(CustomDebug)]
+pub struct Login {
+    #[debug(rename = "user")]
+    username: String,
+    #[debug(redact(len))]
+    password: String,
+    #[debug(redact)]
+    token: Token,
+    #[debug(skip)]
+    attempts: u32,
+}
+
+#[derive(CustomDebug)]
+pub enum Credential<T> {
+    Key {
+        id: u32,
+        #[debug(redact)]
+        secret: T,
+    },
+    Session(#[debug(skip)] T, u64),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let login = Login {
+        username: "ferris".to_owned(),
+        password: "hunter2".to_owned(),
+        token: Token(42),
+        attempts: 3,
+    };
+    assert_eq!(
+        format!("{:?}", login),
+        r#"Login { user: "ferris", password: "<redacted len=7>", token: "<redacted>" }"#,
+    );
+    assert_eq!(login.token.0 + u64::from(login.attempts), 45);
+
+    let key = Credential::Key {
+        id: 1,
+        secret: Token(7),
+    };
+    assert_eq!(format!("{:?}", key), r#"Key { id: 1, secret: "<redacted>" }"#);
+    let session = Credential::Session(Token(8), 99);
+    assert_eq!(format!("{:?}", session), "Session(99)");
+
+    assert_debug::<Credential<Token>>();
+}
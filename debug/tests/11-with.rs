@@ -0,0 +1,67 @@
+// A field marked #[debug(with = "path")] is printed by calling the given
+// function instead of through the field's Debug impl, for output that a format
+// string cannot express:
+//
+//     fn hex(bytes: &[u8], formatter: &mut fmt::Formatter) -> fmt::Result {...}
+//
+// The function is called with a reference to the field, so the usual deref
+// coercions apply, and the field's type need not implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::time::{Duration, SystemTime};
+
+fn hex(bytes: &[u8], formatter: &mut fmt::Formatter) -> fmt::Result {
+    for byte in bytes {
+        write!(formatter, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+fn since_epoch(time: &SystemTime, formatter: &mut fmt::Formatter) -> fmt::Result {
+    let elapsed = time.duration_since(SystemTime::UNIX_EPOCH).unwrap();
+    write!(formatter, "@{}", elapsed.as_secs())
+}
+
+fn opaque<T>(_: &T, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("?")
+}
+
+pub struct Handle {
+    id: u32,
+}
+
+impl Handle {
+    fn by_id(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "#{}", self.id)
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "hex")]
+    payload: Vec<u8>,
+    #[debug(with = "since_epoch")]
+    sent: SystemTime,
+    #[debug(with = "Handle::by_id")]
+    handle: Handle,
+    #[debug(with = "opaque")]
+    extra: T,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let packet = Packet {
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        sent: SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+        handle: Handle { id: 7 },
+        extra: Handle { id: 8 },
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        "Packet { payload: deadbeef, sent: @60, handle: #7, extra: ? }",
+    );
+
+    assert_debug::<Packet<Handle>>();
+}
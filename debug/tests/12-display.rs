@@ -0,0 +1,103 @@
+// The CustomDisplay derive implements Display from a template given in a
+// #[display("...")] attribute. Placeholders name the fields to interpolate,
+// `{name}` for named fields and `{0}` for tuple fields, and take the usual
+// format specs:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{name} ({bitmask:#x})")]
+//     pub struct Field {...}
+//
+// A width or precision can be taken from a `usize` field too, as in
+// `{name:>width$}` or `{1:.0$}`.
+//
+// Enums take a template on each variant, or on the enum for the variants
+// without one; otherwise unit variants display their name. Only the traits that a template actually uses are required of a
+// type parameter.
+
+use derive_debug::CustomDisplay;
+use std::fmt::Display;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({bitmask:#x})")]
+pub struct Field {
+    name: &'static str,
+    bitmask: u16,
+}
+
+#[derive(CustomDisplay)]
+pub enum Event<T> {
+    #[display("connected to {host}:{port}")]
+    Connected { host: String, port: u16 },
+    #[display("received {0:?} after {1}ms, {{ok}}")]
+    Received(T, u64),
+    Closed,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}")]
+pub struct Wrapper<T>(T);
+
+#[derive(CustomDisplay)]
+#[display("{0} units")]
+pub enum Units {
+    Meters(f32),
+    #[display("{0} ft")]
+    Feet(f32),
+    Steps(u32),
+}
+
+#[derive(CustomDisplay)]
+#[display("[{name:>width$}] {ratio:.precision$} {code:0>4}")]
+pub struct Padded {
+    name: &'static str,
+    width: usize,
+    ratio: f64,
+    precision: usize,
+    code: u8,
+}
+
+#[derive(CustomDisplay)]
+#[display("{1:+.0$e}")]
+pub struct Scientific(usize, f64);
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    let field = Field {
+        name: "F",
+        bitmask: 0b00011100,
+    };
+    assert_eq!(field.to_string(), "F (0x1c)");
+
+    let connected: Event<()> = Event::Connected {
+        host: "localhost".to_owned(),
+        port: 8080,
+    };
+    assert_eq!(connected.to_string(), "connected to localhost:8080");
+    assert_eq!(
+        Event::Received(vec![1, 2], 15).to_string(),
+        "received [1, 2] after 15ms, {ok}",
+    );
+    assert_eq!(Event::<()>::Closed.to_string(), "Closed");
+
+    // Received only needs `T: Debug`.
+    #[derive(Debug)]
+    struct OnlyDebug;
+    assert_display::<Event<OnlyDebug>>();
+
+    assert_eq!(Wrapper(5).to_string(), "5");
+
+    assert_eq!(Units::Meters(1.5).to_string(), "1.5 units");
+    assert_eq!(Units::Feet(3.0).to_string(), "3 ft");
+    assert_eq!(Units::Steps(10).to_string(), "10 units");
+
+    let padded = Padded {
+        name: "F",
+        width: 3,
+        ratio: 0.125,
+        precision: 2,
+        code: 7,
+    };
+    assert_eq!(padded.to_string(), "[  F] 0.12 0007");
+    assert_eq!(Scientific(1, 1234.5).to_string(), "+1.2e3");
+}
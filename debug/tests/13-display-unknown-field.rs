@@ -0,0 +1,15 @@
+// Every placeholder in a #[display("...")] template has to name a field, and
+// the error points at the template with the offending placeholder. Stable
+// compilers cannot point into a string literal, so the error covers the whole
+// template there and its message quotes the placeholder and where it starts.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({mask:#x})")]
+pub struct Field {
+    name: &'static str,
+    bitmask: u16,
+}
+
+fn main() {}
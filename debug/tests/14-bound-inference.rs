@@ -0,0 +1,104 @@
+// Bounds are inferred by looking through the types of the fields. A type
+// parameter nested in ordinary wrappers, like `Vec<Option<T>>`, `Rc<RefCell<T>>`
+// or `&'a T`, is bounded with `T: Debug`. Where a parameter only appears in a
+// type that is Debug for any argument, like a function pointer, a raw pointer,
+// PhantomData or Weak, or in a trait object, which can only be Debug through a
+// supertrait, there is no bound on it. Associated types of a parameter are
+// bounded themselves:
+//
+//     impl<'a, A, B, C, D, E, F: Trait, G> Debug for Everything<'a, A, B, C, D, E, F, G>
+//     where
+//         A: Debug,
+//         B: Debug,
+//         <F as Trait>::Value: Debug,
+//     {...}
+//
+// A field can also replace the bounds inferred from its type with its own
+// #[debug(bound = "...")], which may be empty.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+pub trait Trait {
+    type Value;
+}
+
+pub trait Shape<T>: Debug {
+    fn area(&self, scale: T) -> f64;
+}
+
+#[derive(CustomDebug)]
+pub struct Everything<'a, A, B, C, D, E, F: Trait, G> {
+    nested: Vec<Option<A>>,
+    shared: Rc<RefCell<&'a B>>,
+    callback: fn(C) -> C,
+    raw: *const D,
+    parent: Weak<E>,
+    marker: PhantomData<&'a E>,
+    qualified: <F as Trait>::Value,
+    shape: Box<dyn Shape<G>>,
+}
+
+// Borrowed trait objects are not `'static`.
+#[derive(CustomDebug)]
+pub struct Borrowed<'a, G> {
+    shape: &'a dyn Shape<G>,
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+    #[debug(bound = "")]
+    count: PhantomData<T>,
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated type does.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    #[derive(Debug)]
+    struct Square;
+
+    impl Shape<Id> for Square {
+        fn area(&self, _: Id) -> f64 {
+            1.0
+        }
+    }
+
+    assert_debug::<Everything<u8, u8, Id, Id, Id, Id, Id>>();
+    assert_debug::<Wrapper<Id, u8>>();
+
+    let everything: Everything<u8, u8, Id, Id, Id, Id, Id> = Everything {
+        nested: vec![Some(1u8)],
+        shared: Rc::new(RefCell::new(&2u8)),
+        callback: |id: Id| id,
+        raw: std::ptr::null::<Id>(),
+        parent: Weak::<Id>::new(),
+        marker: PhantomData,
+        qualified: 3,
+        shape: Box::new(Square) as Box<dyn Shape<Id>>,
+    };
+    let debug = format!("{:?}", everything);
+    assert!(debug.starts_with("Everything { nested: [Some(1)], shared: RefCell { value: 2 }, "));
+    assert!(debug.ends_with("qualified: 3, shape: Square }"));
+    assert_eq!(everything.shape.area(Id), 1.0);
+
+    let borrowed = Borrowed::<Id> { shape: &Square };
+    assert_eq!(format!("{:?}", borrowed), "Borrowed { shape: Square }");
+}
@@ -0,0 +1,123 @@
+// Without attributes, the output of CustomDebug is exactly that of std's
+// derive, including under `{:#?}` where nested values are printed on their own
+// lines and indented. Fields with a custom format keep it in either mode, and
+// whatever they print is indented along with the rest.
+
+use std::fmt::Debug;
+
+mod custom {
+    use derive_debug::CustomDebug;
+
+    #[derive(CustomDebug)]
+    pub struct Field {
+        pub name: &'static str,
+        pub bitmask: u8,
+        pub inner: Inner,
+        pub shape: Shape,
+        pub values: Vec<Option<u8>>,
+    }
+
+    #[derive(CustomDebug)]
+    pub struct Inner {
+        pub label: String,
+        pub empty: Empty,
+    }
+
+    #[derive(CustomDebug)]
+    pub struct Empty {}
+
+    #[derive(CustomDebug)]
+    pub enum Shape {
+        Circle { radius: f32 },
+        Square(u32, Box<Shape>),
+        Point,
+    }
+
+    #[derive(CustomDebug)]
+    pub struct Formatted {
+        pub name: &'static str,
+        #[debug = "0b{:08b}"]
+        pub bitmask: u8,
+        #[debug = "{:?}"]
+        pub inner: Inner,
+    }
+}
+
+mod std_derive {
+    #[derive(Debug)]
+    pub struct Field {
+        pub name: &'static str,
+        pub bitmask: u8,
+        pub inner: Inner,
+        pub shape: Shape,
+        pub values: Vec<Option<u8>>,
+    }
+
+    #[derive(Debug)]
+    pub struct Inner {
+        pub label: String,
+        pub empty: Empty,
+    }
+
+    #[derive(Debug)]
+    pub struct Empty {}
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    pub enum Shape {
+        Circle { radius: f32 },
+        Square(u32, Box<Shape>),
+        Point,
+    }
+}
+
+fn assert_same(custom: impl Debug, std_derive: impl Debug) {
+    assert_eq!(format!("{:?}", custom), format!("{:?}", std_derive));
+    assert_eq!(format!("{:#?}", custom), format!("{:#?}", std_derive));
+}
+
+fn main() {
+    let custom = custom::Field {
+        name: "F",
+        bitmask: 0b00011100,
+        inner: custom::Inner {
+            label: "in".to_owned(),
+            empty: custom::Empty {},
+        },
+        shape: custom::Shape::Square(
+            2,
+            Box::new(custom::Shape::Circle { radius: 1.5 }),
+        ),
+        values: vec![Some(1), None],
+    };
+    let std_derive = std_derive::Field {
+        name: "F",
+        bitmask: 0b00011100,
+        inner: std_derive::Inner {
+            label: "in".to_owned(),
+            empty: std_derive::Empty {},
+        },
+        shape: std_derive::Shape::Square(
+            2,
+            Box::new(std_derive::Shape::Circle { radius: 1.5 }),
+        ),
+        values: vec![Some(1), None],
+    };
+    assert_same(&custom, &std_derive);
+    assert_same(custom::Shape::Point, std_derive::Shape::Point);
+
+    let formatted = custom::Formatted {
+        name: "F",
+        bitmask: 0b00011100,
+        inner: custom::Inner {
+            label: "in".to_owned(),
+            empty: custom::Empty {},
+        },
+    };
+    let expected = r#"Formatted {
+    name: "F",
+    bitmask: 0b00011100,
+    inner: Inner { label: "in", empty: Empty },
+}"#;
+    assert_eq!(format!("{:#?}", formatted), expected);
+}
@@ -0,0 +1,61 @@
+// #[debug(compact)] on a struct or enum prints it without any whitespace, for
+// logs where every byte counts:
+//
+//     Field{name:"F",bitmask:0b00011100}
+//
+// Fields are printed through their own Debug impls, so nested values are only
+// compact if they opt in too. Under `{:#?}` they are still kept on one line.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(skip)]
+    id: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Log {
+    id: u32,
+    tags: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub enum Event {
+    Key { code: u16, field: Field },
+    Move(i32, i32),
+    Idle,
+    Nothing {},
+}
+
+fn main() {
+    let field = Field {
+        name: "F",
+        bitmask: 0b00011100,
+        id: 1,
+    };
+    assert_eq!(format!("{:?}", field), r#"Field{name:"F",bitmask:0b00011100}"#);
+    assert_eq!(format!("{:#?}", field), format!("{:?}", field));
+    assert_eq!(field.id, 1);
+
+    let key = Event::Key { code: 13, field };
+    assert_eq!(
+        format!("{:?}", key),
+        r#"Key{code:13,field:Field{name:"F",bitmask:0b00011100}}"#,
+    );
+    assert_eq!(format!("{:?}", Event::Move(-1, 2)), "Move(-1,2)");
+    assert_eq!(format!("{:?}", Event::Idle), "Idle");
+    assert_eq!(format!("{:?}", Event::Nothing {}), "Nothing");
+
+    let log = Log {
+        id: 1,
+        tags: vec![1, 2],
+    };
+    assert_eq!(format!("{:#?}", log), "Log{id:1,tags:[1, 2]}");
+}
@@ -0,0 +1,79 @@
+// A field marked #[debug(limit = N)] prints at most N of its elements, followed
+// by a count of the ones left out:
+//
+//     Batch { id: 7, items: [0, 1, 2, ... (97 more)] }
+//
+// The field only needs to be iterable by reference. #[debug(non_exhaustive)]
+// on a struct or enum ends the fields with `..`, the way `finish_non_exhaustive`
+// does, to signal that some are not shown. Both work the same on tuple structs
+// and variants as on named fields.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeSet;
+
+#[derive(CustomDebug)]
+pub struct Batch {
+    id: u32,
+    #[debug(limit = 3)]
+    items: Vec<u32>,
+    #[debug(limit = 16)]
+    tags: BTreeSet<&'static str>,
+}
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub struct Request {
+    method: &'static str,
+    #[debug(skip)]
+    body: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub struct Chunk(u64, #[debug(limit = 2)] Vec<u8>, #[debug(skip)] usize);
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub enum Message {
+    Data(#[debug(limit = 2)] &'static [u8]),
+    Ping {},
+    Close,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact, non_exhaustive)]
+pub enum Compact {
+    Data(u8),
+    Empty(),
+}
+
+fn main() {
+    let batch = Batch {
+        id: 7,
+        items: (0..100).collect(),
+        tags: ["a", "b"].into_iter().collect(),
+    };
+    assert_eq!(
+        format!("{:?}", batch),
+        r#"Batch { id: 7, items: [0, 1, 2, ... (97 more)], tags: ["a", "b"] }"#,
+    );
+
+    let request = Request {
+        method: "GET",
+        body: Vec::new(),
+    };
+    assert_eq!(format!("{:?}", request), r#"Request { method: "GET", .. }"#);
+    assert!(request.body.is_empty());
+
+    let chunk = Chunk(4, vec![1, 2, 3, 4], 0);
+    assert_eq!(format!("{:?}", chunk), "Chunk(4, [1, 2, ... (2 more)], ..)");
+    assert_eq!(chunk.2, 0);
+
+    let data = Message::Data(&[1, 2, 3]);
+    assert_eq!(format!("{:?}", data), "Data([1, 2, ... (1 more)], ..)");
+    assert_eq!(format!("{:?}", Message::Ping {}), "Ping { .. }");
+    assert_eq!(format!("{:?}", Message::Close), "Close");
+
+    assert_eq!(format!("{:?}", Compact::Data(1)), "Data(1,..)");
+    assert_eq!(format!("{:?}", Compact::Empty()), "Empty(..)");
+}
@@ -0,0 +1,114 @@
+// #[derive(LogFields)] generates a `log_fields` method that hands each field to
+// a visitor as a name and a `&dyn Debug`, so that a struct can be logged as
+// key=value pairs, JSON or whatever else without formatting it as one Debug
+// string first. Fields go by the same attributes as with CustomDebug: skipped
+// fields are left out, redacted ones are given as "<redacted>", and format
+// strings, `rename`, `with` and `limit` apply too.
+//
+// A proc-macro crate can only export macros, so the visitor trait is defined
+// by the caller. The generated method uses whichever `FieldVisitor` is in
+// scope:
+//
+//     pub fn log_fields(&self, visitor: &mut impl FieldVisitor)
+//
+// An enum gives the name of the variant it holds as a field called "variant",
+// followed by the fields of that variant.
+
+use derive_debug::LogFields;
+use std::fmt::{self, Debug, Write};
+
+pub trait FieldVisitor {
+    fn field(&mut self, name: &str, value: &dyn Debug);
+}
+
+// `user="ferris" attempts=3`
+#[derive(Default)]
+pub struct KeyValue(String);
+
+impl FieldVisitor for KeyValue {
+    fn field(&mut self, name: &str, value: &dyn Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        write!(self.0, "{}={:?}", name, value).unwrap();
+    }
+}
+
+// `{"user":"\"ferris\"","attempts":"3"}`, keeping every value as a string.
+#[derive(Default)]
+pub struct Json(Vec<(String, String)>);
+
+impl FieldVisitor for Json {
+    fn field(&mut self, name: &str, value: &dyn Debug) {
+        self.0.push((name.to_owned(), format!("{:?}", value)));
+    }
+}
+
+impl Json {
+    fn finish(&self) -> String {
+        let fields: Vec<String> = self
+            .0
+            .iter()
+            .map(|(name, value)| format!("{:?}:{:?}", name, value))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+pub struct Token(u64);
+
+fn hex(value: &u32, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{:#x}", value)
+}
+
+#[derive(LogFields)]
+pub struct Login {
+    #[debug(rename = "user")]
+    username: String,
+    #[debug(redact)]
+    token: Token,
+    #[debug(skip)]
+    password: String,
+    #[debug = "0b{:08b}"]
+    flags: u8,
+    #[debug(with = "hex")]
+    session: u32,
+    #[debug(limit = 2)]
+    roles: Vec<&'static str>,
+}
+
+#[derive(LogFields)]
+pub enum Event<T> {
+    Start { id: u32, payload: T },
+    Stop,
+}
+
+fn main() {
+    let login = Login {
+        username: "ferris".to_owned(),
+        token: Token(0),
+        password: "hunter2".to_owned(),
+        flags: 5,
+        session: 255,
+        roles: vec!["admin", "dev", "ops"],
+    };
+    let _ = (login.token.0, &login.password);
+
+    let mut kv = KeyValue::default();
+    login.log_fields(&mut kv);
+    assert_eq!(
+        kv.0,
+        r#"user="ferris" token="<redacted>" flags=0b00000101 session=0xff roles=["admin", "dev", ... (1 more)]"#,
+    );
+
+    let mut json = Json::default();
+    Event::Start { id: 7, payload: 'x' }.log_fields(&mut json);
+    assert_eq!(
+        json.finish(),
+        r#"{"variant":"\"Start\"","id":"7","payload":"'x'"}"#,
+    );
+
+    let mut kv = KeyValue::default();
+    Event::<char>::Stop.log_fields(&mut kv);
+    assert_eq!(kv.0, r#"variant="Stop""#);
+}
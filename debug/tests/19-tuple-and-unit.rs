@@ -0,0 +1,92 @@
+// CustomDebug also applies to tuple structs, which print like `Name(a, b)`
+// through `debug_tuple`, and to unit structs, which print as `Name`. Without
+// attributes the output is exactly that of std's derive.
+//
+// The fields of a tuple struct take the same attributes as named ones, except
+// `rename`, since they have no name to print:
+//
+//     #[derive(CustomDebug)]
+//     pub struct Rgb(#[debug = "{:#04x}"] u8, u8, #[debug(skip)] u8);
+//
+//     Rgb(0x0a, 20)
+
+use std::fmt::Debug;
+
+mod custom {
+    use derive_debug::CustomDebug;
+
+    #[derive(CustomDebug)]
+    pub struct Pair<T>(pub T, pub &'static str);
+
+    #[derive(CustomDebug)]
+    pub struct Unit;
+
+    #[derive(CustomDebug)]
+    pub struct Empty();
+
+    #[derive(CustomDebug)]
+    pub struct Nested(pub Pair<Unit>, pub Vec<Empty>);
+}
+
+mod std_derive {
+    #[derive(Debug)]
+    pub struct Pair<T>(pub T, pub &'static str);
+
+    #[derive(Debug)]
+    pub struct Unit;
+
+    #[derive(Debug)]
+    pub struct Empty();
+
+    #[derive(Debug)]
+    pub struct Nested(pub Pair<Unit>, pub Vec<Empty>);
+}
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Rgb(#[debug = "{:#04x}"] u8, u8, #[debug(skip)] u8);
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub struct Handle(u32, #[debug(skip)] Vec<u8>);
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub struct Marker;
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Point(i32, i32);
+
+fn assert_same(custom: impl Debug, std_derive: impl Debug) {
+    assert_eq!(format!("{:?}", custom), format!("{:?}", std_derive));
+    assert_eq!(format!("{:#?}", custom), format!("{:#?}", std_derive));
+}
+
+fn main() {
+    assert_same(custom::Pair(1u8, "a"), std_derive::Pair(1u8, "a"));
+    assert_same(custom::Unit, std_derive::Unit);
+    assert_same(custom::Empty(), std_derive::Empty());
+    assert_same(
+        custom::Nested(
+            custom::Pair(custom::Unit, "b"),
+            vec![custom::Empty()],
+        ),
+        std_derive::Nested(
+            std_derive::Pair(std_derive::Unit, "b"),
+            vec![std_derive::Empty()],
+        ),
+    );
+
+    let rgb = Rgb(10, 20, 30);
+    assert_eq!(format!("{:?}", rgb), "Rgb(0x0a, 20)");
+    assert_eq!(rgb.2, 30);
+
+    let handle = Handle(3, Vec::new());
+    assert_eq!(format!("{:?}", handle), "Handle(3, ..)");
+    assert!(handle.1.is_empty());
+    assert_eq!(format!("{:?}", Marker), "Marker");
+
+    assert_eq!(format!("{:?}", Point(1, -2)), "Point(1,-2)");
+}
@@ -0,0 +1,14 @@
+// A width or precision taken from a field has to name one too, and the error
+// points at the template like for the placeholder's own field, quoting the
+// placeholder on stable compilers.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{name:>width$}")]
+pub struct Field {
+    name: &'static str,
+    len: usize,
+}
+
+fn main() {}
@@ -1,12 +1,24 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-skip-redact-rename.rs");
+    t.pass("tests/11-with.rs");
+    t.pass("tests/12-display.rs");
+    t.compile_fail("tests/13-display-unknown-field.rs");
+    t.pass("tests/14-bound-inference.rs");
+    t.pass("tests/15-alternate.rs");
+    t.pass("tests/16-compact.rs");
+    t.pass("tests/17-limit-non-exhaustive.rs");
+    t.pass("tests/18-log-fields.rs");
+    t.pass("tests/19-tuple-and-unit.rs");
+    t.compile_fail("tests/20-display-unknown-count.rs");
 }